@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use bevy_dragndrop::DragPlugin;
+use bevy_dragndrop::*;
+use rand::Rng;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(DragPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (on_dropped, on_hovered).after(DragSystem::Drop))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // Camera
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 9.0, 9.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    let mut rng = rand::rng();
+
+    let pad = meshes.add(Cuboid::new(1.8, 0.1, 1.8));
+    let cube = meshes.add(Cuboid::new(1.0, 1.0, 1.0));
+
+    for x in -2..=2 {
+        for z in -2..=2 {
+            commands.spawn((
+                Mesh3d(pad.clone()),
+                MeshMaterial3d(materials.add(Color::srgb(0.3, 0.3, 0.3))),
+                Transform::from_xyz(x as f32 * 2.0, 0.0, z as f32 * 2.0),
+                Receiver,
+            ));
+        }
+    }
+
+    for _ in 0..5 {
+        let translation = Vec3::new(
+            rng.random_range(-2..=2) as f32 * 2.0,
+            0.55,
+            rng.random_range(-2..=2) as f32 * 2.0,
+        );
+        commands.spawn((
+            Mesh3d(cube.clone()),
+            MeshMaterial3d(materials.add(Color::hsl(rng.random::<f32>() * 360.0, 1.0, 0.5))),
+            Transform::from_translation(translation),
+            Draggable::default(),
+            // Slide the cubes along the top of the pads
+            DragPlane::new(Dir3::Y, Vec3::new(0.0, 0.55, 0.0)),
+        ));
+    }
+}
+
+fn on_dropped(
+    mut er_drop: EventReader<Dropped>,
+    mut q_draggable: Query<&mut Transform, With<Draggable>>,
+    q_receiver: Query<&GlobalTransform, With<Receiver>>,
+) {
+    for event in er_drop.read() {
+        // Snap onto the pad it was dropped on
+        if let Some(received) = event.received {
+            let pad = q_receiver.get(received).unwrap().translation();
+            let mut transform = q_draggable.get_mut(event.dropped).unwrap();
+            transform.translation.x = pad.x;
+            transform.translation.z = pad.z;
+        }
+    }
+}
+
+fn on_hovered(
+    mut er_hovered: EventReader<HoveredChange>,
+    q_receiver: Query<&MeshMaterial3d<StandardMaterial>, With<Receiver>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for event in er_hovered.read() {
+        if let Some(receiver) = event.receiver {
+            let material = q_receiver.get(receiver).unwrap();
+            materials.get_mut(material).unwrap().base_color = Color::srgb(0.45, 0.45, 0.45);
+        }
+        if let Some(receiver) = event.prevreceiver {
+            let material = q_receiver.get(receiver).unwrap();
+            materials.get_mut(material).unwrap().base_color = Color::srgb(0.3, 0.3, 0.3);
+        }
+    }
+}
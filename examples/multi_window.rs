@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use bevy::render::camera::RenderTarget;
+use bevy::window::WindowRef;
+use bevy_dragndrop::DragPlugin;
+use bevy_dragndrop::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(DragPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, on_dropped.after(DragSystem::Drop))
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    // Camera of the primary window
+    commands.spawn(Camera2d);
+
+    // The second window looks at a different part of the world through its own camera
+    let second_window = commands
+        .spawn(Window {
+            title: "Second window".to_string(),
+            ..default()
+        })
+        .id();
+    commands.spawn((
+        Camera2d,
+        Camera {
+            target: RenderTarget::Window(WindowRef::Entity(second_window)),
+            ..default()
+        },
+        Transform::from_xyz(2000.0, 0.0, 0.0),
+    ));
+
+    // One receiver and one draggable in view of each camera
+    for (x, color) in [
+        (0.0, Color::srgb(1.0, 0.4, 0.4)),
+        (2000.0, Color::srgb(0.4, 0.4, 1.0)),
+    ] {
+        commands
+            .spawn((
+                Sprite {
+                    color: Color::srgb(0.1, 0.1, 0.1),
+                    custom_size: Some(Vec2::splat(200.0)),
+                    ..default()
+                },
+                Transform::from_xyz(x + 150.0, 0.0, 0.0),
+                Receiver,
+            ))
+            .observe(on_hover_enter)
+            .observe(on_hover_exit);
+        commands.spawn((
+            Sprite {
+                color,
+                custom_size: Some(Vec2::splat(80.0)),
+                ..default()
+            },
+            Transform::from_xyz(x - 150.0, 0.0, 1.0),
+            Draggable::default(),
+        ));
+    }
+}
+
+fn on_dropped(
+    mut er_drop: EventReader<Dropped>,
+    mut q_draggable: Query<&mut Transform, With<Draggable>>,
+    q_receiver: Query<&GlobalTransform, With<Receiver>>,
+) {
+    for event in er_drop.read() {
+        // Snap into the receiver it was dropped on
+        if let Some(received) = event.received {
+            let receiver = q_receiver.get(received).unwrap().translation();
+            let mut transform = q_draggable.get_mut(event.dropped).unwrap();
+            transform.translation.x = receiver.x;
+            transform.translation.y = receiver.y;
+        }
+    }
+}
+
+fn on_hover_enter(
+    trigger: Trigger<HoverEnter>,
+    mut q_receiver: Query<&mut Sprite, With<Receiver>>,
+) {
+    if let Ok(mut sprite) = q_receiver.get_mut(trigger.target()) {
+        sprite.color = Color::srgb(0.3, 0.3, 0.3);
+    }
+}
+
+fn on_hover_exit(trigger: Trigger<HoverExit>, mut q_receiver: Query<&mut Sprite, With<Receiver>>) {
+    if let Ok(mut sprite) = q_receiver.get_mut(trigger.target()) {
+        sprite.color = Color::srgb(0.1, 0.1, 0.1);
+    }
+}
@@ -0,0 +1,110 @@
+use bevy::diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin};
+use bevy::prelude::*;
+use bevy_dragndrop::DragPlugin;
+use bevy_dragndrop::*;
+
+/// Receivers per side of the board, 60 * 60 = 3600 receivers in total.
+const CELLS: i32 = 60;
+const CELL_SIZE: f32 = 11.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins((
+            FrameTimeDiagnosticsPlugin::default(),
+            LogDiagnosticsPlugin::default(),
+        ))
+        .add_plugins(DragPlugin::default())
+        .add_systems(Startup, setup)
+        .add_systems(Update, (on_dropped, on_hovered).after(DragSystem::Drop))
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    // Camera
+    commands.spawn(Camera2d);
+
+    commands.spawn((
+        Sprite {
+            color: Color::srgb(0.5, 0.5, 0.5),
+            ..default()
+        },
+        Transform::from_scale(Vec3::new(CELL_SIZE - 1.0, CELL_SIZE - 1.0, 1.0)),
+        Visibility::Hidden,
+        Highlight,
+    ));
+
+    let origin = -(CELLS as f32 * CELL_SIZE) / 2.0;
+    for x in 0..CELLS {
+        for y in 0..CELLS {
+            commands.spawn((
+                Sprite {
+                    color: Color::srgb(0.2, 0.2, 0.2),
+                    ..default()
+                },
+                Transform {
+                    scale: Vec3::new(CELL_SIZE - 1.0, CELL_SIZE - 1.0, 1.0),
+                    translation: Vec3::new(
+                        origin + x as f32 * CELL_SIZE,
+                        origin + y as f32 * CELL_SIZE,
+                        0.0,
+                    ),
+                    ..default()
+                },
+                Receiver,
+            ));
+        }
+    }
+
+    for i in 0..10 {
+        commands.spawn((
+            Sprite {
+                color: Color::hsl(i as f32 * 36.0, 1.0, 0.5),
+                ..default()
+            },
+            Transform {
+                scale: Vec3::new(CELL_SIZE * 2.0, CELL_SIZE * 2.0, 1.0),
+                translation: Vec3::new(origin + i as f32 * CELL_SIZE * 6.0, 0.0, 1.0),
+                ..default()
+            },
+            Draggable::default(),
+        ));
+    }
+}
+
+fn on_dropped(
+    mut er_drop: EventReader<Dropped>,
+    mut q_draggable: Query<&mut Transform, With<Draggable>>,
+    q_receiver: Query<&GlobalTransform, With<Receiver>>,
+) {
+    for event in er_drop.read() {
+        // Snap onto the cell it was dropped on
+        if let Some(received) = event.received {
+            let cell = q_receiver.get(received).unwrap().translation();
+            let mut transform = q_draggable.get_mut(event.dropped).unwrap();
+            transform.translation.x = cell.x;
+            transform.translation.y = cell.y;
+        }
+    }
+}
+
+#[derive(Component)]
+struct Highlight;
+
+fn on_hovered(
+    mut er_hovered: EventReader<HoveredChange>,
+    mut q_highlight: Query<(&mut Transform, &mut Visibility), With<Highlight>>,
+    q_receiver: Query<&GlobalTransform, With<Receiver>>,
+) {
+    // Move a single highlight around rather than recoloring receivers, so their bounds never change
+    let (mut transform, mut visibility) = q_highlight.single_mut().unwrap();
+    for event in er_hovered.read() {
+        if let Some(receiver) = event.receiver {
+            let cell = q_receiver.get(receiver).unwrap().translation();
+            transform.translation = Vec3::new(cell.x, cell.y, 0.5);
+            *visibility = Visibility::Visible;
+        } else {
+            *visibility = Visibility::Hidden;
+        }
+    }
+}
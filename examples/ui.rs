@@ -6,9 +6,9 @@ use rand::prelude::*;
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(DragPlugin)
+        .add_plugins(DragPlugin::default())
         .add_systems(Startup, setup)
-        .add_systems(Update, (on_dropped, on_dragged, on_hovered))
+        .add_systems(Update, on_dropped.after(DragSystem::Drop))
         .run();
 }
 
@@ -68,7 +68,10 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                                     BackgroundColor(Color::srgb(0.30, 0.30, 0.30)),
                                     BorderColor(Color::srgb(0.75, 0.75, 0.75)),
                                     Receiver,
+                                    ReceiverMode::Swap,
                                 ))
+                                .observe(on_hover_enter)
+                                .observe(on_hover_exit)
                                 .with_children(|parent| {
                                     parent.spawn((
                                         Node {
@@ -78,9 +81,11 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                                             ..default()
                                         },
                                         GlobalZIndex(1),
-                                        BackgroundColor(
-                                            Color::hsl(rng.random::<f32>() * 360.0, 1.0, 0.5),
-                                        ),
+                                        BackgroundColor(Color::hsl(
+                                            rng.random::<f32>() * 360.0,
+                                            1.0,
+                                            0.5,
+                                        )),
                                         ImageNode::new(icon.clone()),
                                         Draggable::default(),
                                     ));
@@ -92,53 +97,26 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn on_dropped(
-    mut commands: Commands,
     mut er_drop: EventReader<Dropped>,
-    mut q_draggable: Query<(&mut Node, &mut ZIndex), With<Draggable>>,
-    parent: Query<&ChildOf, With<Draggable>>,
-    children: Query<&Children, With<Receiver>>,
+    mut q_draggable: Query<&mut Node, With<Draggable>>,
 ) {
+    // The receivers swap their icon with the one dropped on them, so it only needs to be put back into the layout
     for event in er_drop.read() {
-        if let Some(received) = event.received {
-            let ent_parent = parent.get(event.dropped).unwrap().parent();
-            commands.entity(event.dropped).remove::<ChildOf>();
-
-            let child = children.get(received).unwrap().iter().next().unwrap();
-            commands
-                .entity(received)
-                .remove_children(&[child])
-                .add_child(event.dropped);
-            commands.entity(ent_parent).add_child(child);
-        }
-        let (mut style, mut zindex) = q_draggable.get_mut(event.dropped).unwrap();
+        let mut style = q_draggable.get_mut(event.dropped).unwrap();
         style.left = Val::Auto;
         style.top = Val::Auto;
-        *zindex = ZIndex(-1);
     }
 }
 
-fn on_dragged(
-    mut er_drag: EventReader<Dragged>,
-    mut q_draggable: Query<&mut GlobalZIndex, With<Draggable>>,
-) {
-    for event in er_drag.read() {
-        let mut zindex = q_draggable.get_mut(event.dragged).unwrap();
-        *zindex = GlobalZIndex(15);
+// Observed on each receiver, so these only run for the receiver that is hovered over
+fn on_hover_enter(trigger: Trigger<HoverEnter>, mut q_receiver: Query<&mut BackgroundColor>) {
+    if let Ok(mut color) = q_receiver.get_mut(trigger.target()) {
+        *color = Color::srgb(0.45, 0.45, 0.45).into();
     }
 }
 
-fn on_hovered(
-    mut er_hovered: EventReader<HoveredChange>,
-    mut q_receiver: Query<&mut BackgroundColor, With<Receiver>>,
-) {
-    for event in er_hovered.read() {
-        if let Some(receiver) = event.receiver {
-            let mut color = q_receiver.get_mut(receiver).unwrap();
-            *color = Color::srgb(0.45, 0.45, 0.45).into();
-        }
-        if let Some(receiver) = event.prevreceiver {
-            let mut color = q_receiver.get_mut(receiver).unwrap();
-            *color = Color::srgb(0.3, 0.3, 0.3).into();
-        }
+fn on_hover_exit(trigger: Trigger<HoverExit>, mut q_receiver: Query<&mut BackgroundColor>) {
+    if let Ok(mut color) = q_receiver.get_mut(trigger.target()) {
+        *color = Color::srgb(0.3, 0.3, 0.3).into();
     }
 }
@@ -6,9 +6,9 @@ use rand::Rng;
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
-        .add_plugins(DragPlugin)
+        .add_plugins(DragPlugin::default())
         .add_systems(Startup, setup)
-        .add_systems(Update, (on_dropped, on_dragged, on_hovered))
+        .add_systems(Update, on_dropped.after(DragSystem::Drop))
         .run();
 }
 
@@ -74,7 +74,10 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                                                 ..default()
                                             },
                                             Receiver,
+                                            ReceiverMode::Swap,
                                         ))
+                                        .observe(on_hover_enter)
+                                        .observe(on_hover_exit)
                                         .with_children(|parent| {
                                             parent.spawn((
                                                 Sprite {
@@ -98,6 +101,8 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                                                     disallowed: InputFlags::RightClick
                                                         | InputFlags::MiddleClick,
                                                     minimum_held: Some(0.15),
+                                                    z_boost: Some(14.0),
+                                                    ..default()
                                                 },
                                             ));
                                         });
@@ -109,51 +114,28 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn on_dropped(
-    mut commands: Commands,
     mut er_drop: EventReader<Dropped>,
     mut q_draggable: Query<&mut Transform, With<Draggable>>,
-    parent: Query<&ChildOf, With<Draggable>>,
-    children: Query<&Children, With<Receiver>>,
 ) {
+    // The receivers swap their icon with the one dropped on them, so it only needs to be centered in its new slot
     for event in er_drop.read() {
-        if let Some(received) = event.received {
-            let ent_parent = parent.get(event.dropped).unwrap().parent();
-            commands.entity(event.dropped).remove::<ChildOf>();
-
-            let child = children.get(received).unwrap().iter().next().unwrap();
-            commands
-                .entity(received)
-                .remove_children(&[child])
-                .add_child(event.dropped);
-            commands.entity(ent_parent).add_child(child);
-        }
         let mut transform = q_draggable.get_mut(event.dropped).unwrap();
         transform.translation = Vec3::new(0.0, 0.0, 1.0);
     }
 }
 
-fn on_dragged(
-    mut er_drag: EventReader<Dragged>,
-    mut q_draggable: Query<&mut Transform, With<Draggable>>,
+// Each receiver observes its own hovers, so only the slot being hovered is touched
+fn on_hover_enter(
+    trigger: Trigger<HoverEnter>,
+    mut q_receiver: Query<&mut Sprite, With<Receiver>>,
 ) {
-    for event in er_drag.read() {
-        let mut transform = q_draggable.get_mut(event.dragged).unwrap();
-        transform.translation.z = 15.0;
+    if let Ok(mut sprite) = q_receiver.get_mut(trigger.target()) {
+        sprite.color = Color::srgb(0.3, 0.3, 0.3);
     }
 }
 
-fn on_hovered(
-    mut er_hovered: EventReader<HoveredChange>,
-    mut q_receiver: Query<&mut Sprite, With<Receiver>>,
-) {
-    for event in er_hovered.read() {
-        if let Some(receiver) = event.receiver {
-            let mut sprite = q_receiver.get_mut(receiver).unwrap();
-            sprite.color = Color::srgb(0.3, 0.3, 0.3);
-        }
-        if let Some(receiver) = event.prevreceiver {
-            let mut sprite = q_receiver.get_mut(receiver).unwrap();
-            sprite.color = Color::srgb(0.1, 0.1, 0.1);
-        }
+fn on_hover_exit(trigger: Trigger<HoverExit>, mut q_receiver: Query<&mut Sprite, With<Receiver>>) {
+    if let Ok(mut sprite) = q_receiver.get_mut(trigger.target()) {
+        sprite.color = Color::srgb(0.1, 0.1, 0.1);
     }
 }
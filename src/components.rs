@@ -0,0 +1,749 @@
+//! Components that make entities draggable or receivers, and that the plugin adds while they are dragged.
+
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use crate::input::InputFlags;
+
+/// Component that may be attached to anything with a transform and GlobalTransform component to give it draggable functionality.
+///
+/// Entities without a `Sprite` or UI node that have an `Aabb`, such as meshes, are hit tested by casting the cursor's ray against it,
+/// which allows dragging in 3D. Add a `DragPlane` to control where such entities move while they are dragged.
+///
+/// When several draggables are under the cursor, the one nested deepest in another is grabbed, then the one drawn on
+/// top: UI nodes above entities in the world, by their stacking order, and entities in the world by the highest z
+/// translation, or the nearest point the cursor's ray hits for those hit tested through their `Aabb`. Remaining ties go to the highest `Entity`, usually the one spawned last, so the same one is grabbed
+/// every time. Receivers break their ties the same way, see `ReceiverPriority`.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Draggable {
+    /// All of these inputs must be pressed down for dragging to initiate, which happens when one of the clicks among
+    /// them is pressed.
+    pub required: InputFlags,
+    /// Dragging will not initiate if any of these are held down.
+    pub disallowed: InputFlags,
+    /// Minimum amount of time for buttons to be held before dragging initiates in seconds. The cursor must stay over
+    /// the entity in the meantime, or the drag doesn't start.
+    pub minimum_held: Option<f64>,
+    /// If set, waiting out `minimum_held` is cancelled once the cursor is further than this from where it pressed, in
+    /// logical pixels, like long presses on touch screens. Movement within it doesn't restart the wait.
+    pub hold_slop: Option<f32>,
+    /// If true, the entity is automatically added back to its original parent when it is dropped without a receiver.
+    pub restore_parent: bool,
+    /// Which point of the entity is held under the cursor while it is dragged.
+    pub grab_offset: GrabMode,
+    /// If true, the entity keeps sliding with the velocity it was released at, see `DragInertia`.
+    /// Entities with a `DragPlane` are not affected.
+    pub inertia: bool,
+    /// What happens when the buttons are released while the cursor is outside of the window.
+    pub outside_release: OutsideRelease,
+    /// What happens when the entity is dropped without a receiver.
+    pub drop_outside: DropOutside,
+    /// If set, the drag is cancelled once the cursor is further than this from where it grabbed the entity, in
+    /// logical pixels.
+    pub max_drag_distance: Option<f32>,
+    /// What happens when the window loses focus or the cursor mid-drag, which would otherwise miss the buttons being
+    /// released. Drags started by a `DragCommand` are not affected.
+    pub on_lost: LostCursor,
+    /// Which of the required click buttons must be released for the entity to be dropped.
+    pub release: ReleasePolicy,
+    /// If false, the entity can't be dragged, and an ongoing drag of it is cancelled.
+    pub enabled: bool,
+    /// Amount added to the z translation of entities in the world while they are dragged, so they render above the
+    /// others, restored once the drag ends. Entities with a `DragPlane` are not affected, and UI nodes are raised
+    /// with `DragZIndex` instead.
+    pub z_boost: Option<f32>,
+    /// If false, the plugin doesn't touch the entity's transform, node, parent, z-index or visibility while it is
+    /// dragged, and leaves moving it to the app through `DragMove`. Hovering, dropping and the other events work as
+    /// usual, hit tested at the cursor. `DropOutside::Return` and `inertia` have no effect on such entities.
+    pub follow_cursor: bool,
+    /// If true, a `DragOffset` holding the entity at the point the cursor grabbed it is inserted each time it is
+    /// grabbed, replacing the one it had. See `DragOffset::from_grab`.
+    pub insert_offset: bool,
+}
+
+/// Determines which of a draggable's required click buttons must be released to drop it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum ReleasePolicy {
+    /// The entity is dropped as soon as any of the required click buttons is released.
+    AnyRequiredUp,
+    /// The entity is dropped once all of the required click buttons are released.
+    #[default]
+    AllRequiredUp,
+}
+
+impl ReleasePolicy {
+    /// Whether a drag requiring `required` is released with the current `inputs`.
+    pub(crate) fn released(self, required: InputFlags, inputs: InputFlags) -> bool {
+        let clicks = required.clicks();
+        match self {
+            ReleasePolicy::AnyRequiredUp => !inputs.contains(clicks) || clicks.is_empty(),
+            ReleasePolicy::AllRequiredUp => !inputs.intersects(clicks),
+        }
+    }
+}
+
+/// Determines how a drag ends when the buttons are released while the cursor is outside of the window.
+/// Until then, the entity stays where the cursor was last seen.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum OutsideRelease {
+    /// The entity is dropped without a receiver.
+    #[default]
+    Drop,
+    /// The drag is cancelled and `DragCancelled` is sent.
+    Cancel,
+}
+
+/// Determines what happens when an entity is dropped without a receiver, including when the receiver under it rejected
+/// it with `DropValidation`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum DropOutside {
+    /// The entity stays where it was dropped, which suits freeform canvases.
+    #[default]
+    Keep,
+    /// `Dropped` is sent, then the entity is put back where it was before it was dragged, in its original parent.
+    Return,
+    /// The drag is cancelled and `DragCancelled` is sent instead of `Dropped`.
+    Cancel,
+}
+
+/// Determines what happens to a drag when its window loses focus, or the cursor leaves it or can't be found for more
+/// than a frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum LostCursor {
+    /// The entity waits where the cursor was last seen until it comes back.
+    #[default]
+    Wait,
+    /// The entity is dropped as if the buttons were released, so `Draggable::drop_outside` decides whether it stays
+    /// there or returns to where it was grabbed from.
+    Drop,
+    /// The drag is cancelled and `DragCancelled` is sent.
+    Cancel,
+}
+
+/// Determines which point of a draggable stays under the cursor while it is being dragged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum GrabMode {
+    /// The entity is centered on the cursor.
+    Center,
+    /// The entity keeps the offset between the cursor and the entity at the moment it was grabbed.
+    #[default]
+    Cursor,
+}
+
+impl Default for Draggable {
+    fn default() -> Self {
+        Draggable {
+            required: InputFlags::LeftClick,
+            disallowed: InputFlags::RightClick | InputFlags::MiddleClick,
+            minimum_held: None,
+            hold_slop: None,
+            restore_parent: false,
+            grab_offset: GrabMode::Cursor,
+            inertia: false,
+            outside_release: OutsideRelease::Drop,
+            drop_outside: DropOutside::Keep,
+            max_drag_distance: None,
+            on_lost: LostCursor::Wait,
+            release: ReleasePolicy::AllRequiredUp,
+            enabled: true,
+            z_boost: None,
+            follow_cursor: true,
+            insert_offset: false,
+        }
+    }
+}
+
+impl Draggable {
+    /// Draggable with the default settings, dragged with the left click unless the right or middle one is held.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draggable dragged with the left click, the same as `Draggable::new()`.
+    pub fn left_click() -> Self {
+        Self::new().require(InputFlags::LeftClick)
+    }
+
+    /// Draggable dragged with the left click once it has been held for `secs` seconds.
+    pub fn with_hold(secs: f64) -> Self {
+        Self::new().hold_secs(secs)
+    }
+
+    /// Sets the inputs that must all be pressed to start dragging. They are removed from the disallowed inputs, since
+    /// the entity could never be dragged otherwise.
+    pub fn require(mut self, inputs: InputFlags) -> Self {
+        self.required = inputs;
+        self.disallowed.remove(inputs);
+        self
+    }
+
+    /// Sets the inputs that prevent dragging from starting while any of them is pressed. They are removed from the
+    /// required inputs, since the entity could never be dragged otherwise.
+    pub fn disallow(mut self, inputs: InputFlags) -> Self {
+        self.disallowed = inputs;
+        self.required.remove(inputs);
+        self
+    }
+
+    /// Sets how long, in seconds, the buttons must be held before dragging starts.
+    pub fn hold_secs(mut self, secs: f64) -> Self {
+        self.minimum_held = Some(secs);
+        self
+    }
+
+    /// Sets how far, in logical pixels, the cursor can move while the buttons are held before dragging starts.
+    pub fn hold_slop(mut self, slop: f32) -> Self {
+        self.hold_slop = Some(slop);
+        self
+    }
+}
+
+/// Component used to designate when an object is actively being dragged.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Dragging {
+    pub hovering: Option<Entity>,
+    pub reparented: bool,
+    /// Parent the entity had before it was reparented to the root, if any.
+    pub original_parent: Option<Entity>,
+    /// Offset between the cursor and the entity's position, measured when the entity was grabbed.
+    /// This is in logical pixels from the top left corner for UI nodes, and in world units from the translation otherwise.
+    /// Only entities with a `DragPlane` make use of the z axis.
+    pub grab_offset: Vec3,
+    /// Z-index the entity had before dragging raised it, if dragging raised it. Restored once the drag ends.
+    pub saved_z_index: Option<SavedZIndex>,
+    /// Z translation the entity had before `Draggable::z_boost` raised it, if it was raised. Restored once the drag ends.
+    pub saved_z: Option<f32>,
+    /// Most recent positions of the entity along with the time they were recorded at, used to compute inertia.
+    history: VecDeque<(Vec2, f64)>,
+    /// Whether the drag was started by a `DragCommand`, in which case releasing mouse buttons doesn't drop it.
+    pub(crate) manual: bool,
+    /// Cursor position and node offset when dragging started, for UI entities that are kept in their container.
+    pub(crate) container_start: Option<(Vec2, Vec2)>,
+    /// Offset from this entity's grab offset to the grabbed entity's, for entities dragged along with a selection.
+    /// Zero for the entity that was grabbed.
+    pub(crate) group_offset: Vec3,
+    /// Inputs when the drag started or the modifiers last changed, to detect modifier changes.
+    pub(crate) inputs: InputFlags,
+    /// Time the entity started hovering over its current receiver, or stopped hovering over one.
+    pub(crate) hover_started: f64,
+    /// Number of times `HoverDwell` was sent for the current receiver.
+    pub(crate) dwells: u32,
+    /// Camera whose window the drag follows the cursor in. Drags started by a `DragCommand` pick the first window
+    /// the cursor is seen in.
+    pub(crate) camera: Option<Entity>,
+    /// Full receiver the entity is over, above the receiver it is hovering if any.
+    pub(crate) hovering_full: Option<Entity>,
+    /// `ReorderList` the entity is hovering over, or whose child it is hovering over, with the index it would be
+    /// inserted at.
+    pub(crate) reorder: Option<(Entity, usize)>,
+    /// How far the entity was moved with `DragNudge`, with positive y pointing up.
+    pub(crate) nudge: Vec2,
+    /// Logical cursor position the entity was grabbed at, for entities nudged with `NudgeMode::Replace`.
+    pub(crate) nudge_anchor: Option<Vec2>,
+    /// Transform and node the entity had before it was first moved, for `DropOutside::Return`.
+    pub(crate) origin: Option<(Transform, Option<Node>)>,
+    /// Logical cursor position the entity was grabbed at, or first seen at for drags started by a `DragCommand`, for
+    /// `Draggable::max_drag_distance`.
+    pub(crate) grab_screen_position: Option<Vec2>,
+    /// Number of frames in a row the cursor has been lost for, for `Draggable::on_lost`.
+    pub(crate) lost_frames: u32,
+    /// Whether the drag lost the cursor with `LostCursor::Drop`, which drops the entity without the buttons being
+    /// released.
+    pub(crate) lost: bool,
+    /// Logical and world cursor positions from the previous frame, for `DragMove`.
+    pub(crate) last_cursor: Option<(Vec2, Vec2)>,
+}
+
+impl Dragging {
+    /// Number of positions kept to compute the release velocity from.
+    const HISTORY_LEN: usize = 5;
+
+    pub(crate) fn new(grab_offset: Vec3, camera: Option<Entity>, inputs: InputFlags) -> Self {
+        Dragging {
+            hovering: None,
+            reparented: false,
+            original_parent: None,
+            grab_offset,
+            saved_z_index: None,
+            saved_z: None,
+            history: VecDeque::with_capacity(Self::HISTORY_LEN),
+            manual: false,
+            container_start: None,
+            group_offset: Vec3::ZERO,
+            inputs,
+            hover_started: 0.0,
+            dwells: 0,
+            camera,
+            hovering_full: None,
+            reorder: None,
+            nudge: Vec2::ZERO,
+            nudge_anchor: None,
+            origin: None,
+            grab_screen_position: None,
+            lost_frames: 0,
+            lost: false,
+            last_cursor: None,
+        }
+    }
+
+    /// Index the entity was last seen to be inserted at in a `ReorderList`, if it is dropped on the receiver it was
+    /// hovering over.
+    pub(crate) fn reorder_index(&self, received: Option<Entity>) -> Option<usize> {
+        self.reorder
+            .filter(|_| received.is_some() && received == self.hovering)
+            .map(|(_, index)| index)
+    }
+
+    pub(crate) fn start_hover(&mut self, time: f64) {
+        self.hover_started = time;
+        self.dwells = 0;
+    }
+
+    /// Position the entity was last moved to during this drag, if it has been moved yet.
+    pub(crate) fn last_position(&self) -> Option<Vec2> {
+        self.history.back().map(|(position, _)| *position)
+    }
+
+    pub(crate) fn record_position(&mut self, position: Vec2, time: f64) {
+        if self.history.len() == Self::HISTORY_LEN {
+            self.history.pop_front();
+        }
+        self.history.push_back((position, time));
+    }
+
+    /// Average velocity over the recorded positions.
+    pub(crate) fn velocity(&self) -> Vec2 {
+        match (self.history.front(), self.history.back()) {
+            (Some((first, start)), Some((last, end))) if end > start => {
+                (*last - *first) / (end - start) as f32
+            }
+            _ => Vec2::ZERO,
+        }
+    }
+}
+
+/// Z-index of an entity from before it was raised for dragging.
+#[derive(Clone, Copy, Debug, Reflect)]
+pub enum SavedZIndex {
+    /// The entity was given a `ZIndex`, and had this one beforehand if any.
+    Local(Option<ZIndex>),
+    /// The entity was given a `GlobalZIndex`, and had this one beforehand if any.
+    Global(Option<GlobalZIndex>),
+}
+
+/// Component used to designate when an object is waiting to be able to be dragged.
+///
+/// The wait is abandoned if the inputs stop matching the `Draggable`, if the cursor moves further than
+/// `Draggable::hold_slop` or if it leaves the entity before the wait ends, unless `DragSettings::hold_in_bounds` is
+/// turned off.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct AwaitingDrag {
+    /// Time the wait started at, in seconds since startup on the clock chosen by `DragSettings::hold_clock`.
+    pub started: f64,
+    /// Time the wait ends at, on the same clock as `started`.
+    pub ends: f64,
+    /// How far along the wait is, from 0 when the buttons were pressed to 1 when dragging initiates. Updated every
+    /// frame, which makes it suited to drawing a "hold to pick up" indicator. The component is removed, and
+    /// `DragAwaitCancelled` sent, if the wait is abandoned.
+    pub progress: f32,
+    /// Grab offset that is passed on to `Dragging` once the drag initiates.
+    pub grab_offset: Vec3,
+    /// Camera the entity was grabbed through, whose window the drag will follow the cursor in.
+    pub camera: Entity,
+    /// Other selected entities that will be dragged along with this one, with their grab offsets. See `Selected`.
+    pub group: Vec<(Entity, Vec3)>,
+    /// Where the cursor pressed on the entity, in logical window coordinates.
+    pub screen_position: Vec2,
+    /// Where the cursor pressed on the entity on the 2D world plane.
+    pub world_position: Vec2,
+}
+
+impl AwaitingDrag {
+    /// How far along the wait is at the given time, on the clock chosen by `DragSettings::hold_clock`, from 0 to 1.
+    pub fn progress_at(&self, time: f64) -> f32 {
+        let duration = self.ends - self.started;
+        if duration <= 0.0 {
+            return 1.0;
+        }
+        ((time - self.started) / duration).clamp(0.0, 1.0) as f32
+    }
+}
+
+/// Component that may be attached to anything with a transform and GlobalTransform component to allow it to be detected when a draggable is dropped over it.
+///
+/// Like draggables, receivers without a `Sprite` or UI node that have an `Aabb`, such as meshes, are hit tested by casting the cursor's ray against it.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct Receiver;
+
+/// Component that makes a receiver a list whose children can be reordered by dragging them. While an entity is dragged
+/// over it, the index it would be inserted at is found by comparing the cursor with the centers of the children, which
+/// is sent with `ReorderHover` and `Dropped::index`.
+///
+/// The children can be receivers themselves, such as to also let items be dropped onto each other. Hovering over them
+/// counts as hovering over the list.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct ReorderList {
+    /// Direction the children are laid out in.
+    pub axis: ReorderAxis,
+}
+
+/// Direction the children of a `ReorderList` are laid out in, from the first to the last.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum ReorderAxis {
+    /// Top to bottom.
+    #[default]
+    Vertical,
+    /// Left to right.
+    Horizontal,
+}
+
+/// Component that makes the plugin parent entities dropped on a receiver to it, instead of leaving that to your own
+/// `Dropped` handler. Receivers without it are left alone.
+///
+/// `Swap` and `Replace` make room for the dropped entity, so don't combine them with a `ReceiverCapacity`, which
+/// would reject it first.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub enum ReceiverMode {
+    /// The dropped entity is swapped with the receiver's children, which are moved to the parent it was dragged from.
+    /// The first of them is reported as `Dropped::displaced`.
+    Swap,
+    /// The receiver's children are despawned, leaving only the dropped entity.
+    Replace,
+    /// The dropped entity is added to the receiver's children, after the ones it already has.
+    #[default]
+    Stack,
+}
+
+/// Component that temporarily turns a receiver off without removing `Receiver`, such as for a locked inventory slot.
+/// Disabled receivers are ignored as if they were absent. If one is hovered when it is disabled, `HoveredChange` is
+/// sent as the dragged entity stops hovering it.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct ReceiverDisabled;
+
+/// Component that decides which of several overlapping receivers under the cursor is hovered and dropped on.
+/// Receivers with a higher priority win, and receivers without this component have a priority of 0. Between receivers
+/// of the same priority, the one nested deepest in the hierarchy wins, so a receiver nested in another is preferred
+/// over it. Remaining ties go to the one drawn on top, like between draggables, see `Draggable`.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Reflect)]
+#[reflect(Component)]
+pub struct ReceiverPriority(pub i32);
+
+/// Component that marks a draggable as selected. Grabbing any selected entity drags every selected entity along with it,
+/// keeping where they are relative to each other, and `Dragged` and `Dropped` are sent for each of them.
+///
+/// Each entity hovers over and is dropped on the receiver under the point of it that is held like the grabbed entity,
+/// that is the cursor moved by how far the entity is from the grabbed one.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Selected;
+
+/// Component that replaces the bounds a draggable or receiver is hit tested with, which are otherwise computed from
+/// its `Sprite`, UI node or transform. This allows grabbable areas that differ from what is displayed, and receivers
+/// that display nothing at all.
+///
+/// Sizes and offsets are in the same units as `Val::Px` for UI nodes, so they follow `UiScale`. Otherwise, rectangles are in world units, while circles are in
+/// the entity's local space and so are scaled by its transform.
+///
+/// `Absolute` hitboxes stay in place whatever the entity's position, such as for a drop zone covering one side of the
+/// screen. They are in `Val::Px` units from the top left corner of the viewport for UI nodes, and in 2D world
+/// coordinates otherwise.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub enum DragHitbox {
+    Rect {
+        /// Size of the hitbox.
+        size: Vec2,
+        /// Offset of the hitbox's center from the entity's position.
+        offset: Vec2,
+    },
+    Circle {
+        /// Radius of the hitbox.
+        radius: f32,
+        /// Offset of the hitbox's center from the entity's position.
+        offset: Vec2,
+    },
+    Absolute {
+        /// Area covered by the hitbox.
+        rect: Rect,
+    },
+}
+
+impl DragHitbox {
+    pub fn rect(size: Vec2, offset: Vec2) -> Self {
+        Self::Rect { size, offset }
+    }
+
+    pub fn circle(radius: f32, offset: Vec2) -> Self {
+        Self::Circle { radius, offset }
+    }
+
+    pub fn absolute(rect: Rect) -> Self {
+        Self::Absolute { rect }
+    }
+}
+
+/// Component that makes a sprite draggable or receiver only hit where its image is opaque enough, rather than anywhere
+/// in its rectangle, for irregularly shaped sprites. Images whose data isn't kept on the CPU, or isn't in a format
+/// that can be read, fall back to the rectangle. Has no effect alongside a `DragHitbox`.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct PixelPerfect {
+    /// Texels with an alpha at or below this are not hit.
+    pub alpha_threshold: f32,
+}
+
+impl Default for PixelPerfect {
+    fn default() -> Self {
+        PixelPerfect {
+            alpha_threshold: 0.5,
+        }
+    }
+}
+
+/// Component that groups draggables and receivers into kinds. A receiver with a kind only accepts draggables of the
+/// same kind, and is ignored as if it was absent while other entities are dragged over it. Receivers without a kind
+/// accept every draggable.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+pub struct DragKind(pub u32);
+
+/// Component that puts a draggable in groups, as a bitmask, for receivers with `DragAccepts` to filter on.
+/// Unlike `DragKind`, a draggable may belong to several groups at once.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+pub struct DragGroup(pub u32);
+
+/// Component that makes a receiver only accept draggables with a `DragGroup` sharing at least one group with this
+/// bitmask. Other draggables ignore the receiver as if it was absent, so it is neither hovered nor dropped on.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Component)]
+pub struct DragAccepts(pub u32);
+
+/// Component that opts a receiver into having `Hovered` inserted while a dragged entity hovers over it, so that
+/// highlighting can be driven by queries rather than by tracking `HoveredChange` events.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct DropZone;
+
+/// Component that the plugin keeps on `DropZone` receivers while at least one dragged entity hovers over them.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct Hovered;
+
+/// Component that limits how many children a receiver may have, such as an inventory slot holding one item.
+/// Full receivers are ignored as if they were absent, so entities fall through to the receiver under them, if any.
+/// The dragged entity doesn't count towards the capacity of the receiver it is being dragged out of.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct ReceiverCapacity(pub usize);
+
+/// Component that the plugin keeps on full receivers while a dragged entity that they would otherwise accept is over
+/// them, for example to highlight them in red. See `ReceiverCapacity`.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct HoveredFull;
+
+/// Component that makes a receiver send `HoverDwell` once a dragged entity hovers over it continuously for `duration` seconds.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DwellTrigger {
+    /// Time in seconds the entity must hover over the receiver before `HoverDwell` is sent.
+    pub duration: f64,
+    /// If set, `HoverDwell` is sent again every this many seconds while the entity keeps hovering, such as to
+    /// scroll a list step by step. Otherwise it is sent once per hover.
+    pub repeat: Option<f64>,
+}
+
+impl Default for DwellTrigger {
+    fn default() -> Self {
+        DwellTrigger {
+            duration: 0.5,
+            repeat: None,
+        }
+    }
+}
+
+impl DwellTrigger {
+    /// Time hovered after which `HoverDwell` is next sent, once it has been sent `dwells` times already.
+    pub(crate) fn next_dwell(&self, dwells: u32) -> Option<f64> {
+        match (dwells, self.repeat) {
+            (0, _) => Some(self.duration),
+            (_, Some(repeat)) => Some(self.duration + repeat * dwells as f64),
+            (_, None) => None,
+        }
+    }
+}
+
+/// Component that lets a receiver reject entities dropped on it, such as when a slot is full or the entity is of the
+/// wrong kind. The function is given the dropped entity and the receiver, and returns whether the drop is accepted.
+///
+/// A rejected entity is dropped as if it missed every receiver: `Dropped` is sent with `received: None`, and the
+/// entity goes back to its original parent if `Draggable::restore_parent` is set.
+///
+/// Unlike the other components, it holds a closure and so can't be reflected or loaded from a scene.
+#[derive(Component)]
+pub struct DropValidation(pub Box<dyn Fn(EntityRef, EntityRef) -> bool + Send + Sync>);
+
+impl DropValidation {
+    pub fn new(validate: impl Fn(EntityRef, EntityRef) -> bool + Send + Sync + 'static) -> Self {
+        Self(Box::new(validate))
+    }
+}
+
+/// Component that restricts which region of a draggable can be grabbed, such as the title bar of a panel.
+/// A handle belongs to its nearest `Draggable` ancestor. Once a draggable has at least one handle, dragging
+/// only initiates while the cursor is over one of its handles, but the whole draggable still moves.
+///
+/// When draggables are nested, the innermost one under the cursor is grabbed. A handle placed inside a nested
+/// draggable belongs to the nested one, so an outer draggable with handles is only grabbed through handles that
+/// are outside of the draggables nested in it.
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct DragHandle;
+
+/// Component that lets a dragged entity be moved with the arrow keys or the left stick of a gamepad, for example for
+/// accessibility, sending `DragNudged` as it moves. Entities with a `DragPlane` are not affected.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DragNudge {
+    /// Speed the entity is moved at, in logical pixels per second for UI nodes and in world units per second otherwise.
+    pub speed: f32,
+    /// Whether the entity still follows the cursor while it is nudged.
+    pub mode: NudgeMode,
+}
+
+impl Default for DragNudge {
+    fn default() -> Self {
+        DragNudge {
+            speed: 200.0,
+            mode: NudgeMode::Additive,
+        }
+    }
+}
+
+/// Determines how nudging a dragged entity with `DragNudge` combines with the cursor.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum NudgeMode {
+    /// The entity follows the cursor, offset by how far it was nudged.
+    #[default]
+    Additive,
+    /// The entity stays where the cursor grabbed it, and only moves when nudged.
+    Replace,
+}
+
+/// Component that defines drag offset for an entity during dragging, replacing the offset measured when it was grabbed.
+/// It is in logical pixels from the cursor to the top left corner for UI nodes, and to the translation otherwise.
+/// For entities in the world it is converted to world units through the camera, so it stays the same size on screen
+/// at any zoom.
+#[derive(Component, Clone, Copy, Default, Reflect)]
+#[reflect(Component)]
+pub struct DragOffset {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl DragOffset {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+
+    /// Offset that holds the entity at the point the cursor grabbed it, from the logical screen positions of the
+    /// entity, its top left corner for UI nodes, and of the cursor, such as `Dragged::screen_position`.
+    ///
+    /// Draggables with `Draggable::insert_offset` set are given one computed this way each time they are grabbed.
+    pub fn from_grab(entity_pos: Vec2, cursor_pos: Vec2) -> Self {
+        let offset = cursor_pos - entity_pos;
+        Self::new(offset.x, offset.y)
+    }
+}
+
+/// Component that keeps a released draggable sliding, inserted on drop when `Draggable::inertia` is set.
+/// The velocity decays over time and the component is removed once it becomes negligible, or when the entity is dragged again.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DragInertia {
+    /// Velocity in logical pixels per second for UI nodes, and in world units per second otherwise.
+    pub velocity: Vec2,
+    /// Rate at which the velocity decays, a higher value stops the entity sooner.
+    pub damping: f32,
+}
+
+impl DragInertia {
+    /// Damping given to inertia inserted by the plugin.
+    pub const DEFAULT_DAMPING: f32 = 5.0;
+    /// Speed below which inertia is considered to have stopped.
+    pub(crate) const MIN_SPEED: f32 = 1.0;
+}
+
+/// Component that makes a dragged entity ease towards the cursor rather than snapping to it every frame.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DragSmoothing {
+    /// Rate at which the entity approaches the cursor, a higher value follows it more tightly.
+    /// Each second the remaining distance shrinks by a factor of `e^lerp`.
+    pub lerp: f32,
+    /// If true, receivers are detected under the entity's smoothed position rather than under the cursor.
+    pub hit_test_entity: bool,
+}
+
+impl DragSmoothing {
+    pub fn new(lerp: f32) -> Self {
+        Self {
+            lerp,
+            hit_test_entity: false,
+        }
+    }
+}
+
+/// Component that defines the z-index a UI entity is given while it is being dragged.
+/// UI entities without this component use `DragSettings::dragged_z_index`, which is `DragZIndex::Local(1000)` by default.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub enum DragZIndex {
+    /// Insert a `ZIndex` with the given value.
+    Local(i32),
+    /// Insert a `GlobalZIndex` with the given value.
+    Global(i32),
+}
+
+impl Default for DragZIndex {
+    fn default() -> Self {
+        DragZIndex::Local(1000)
+    }
+}
+
+/// Component that decides whether a dragged UI entity is taken out of its container, overriding
+/// `DragSettings::reparent_ui`.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DragReparent(pub bool);
+
+/// Component that makes a draggable move along a plane while it is being dragged, rather than following the cursor in 2D.
+/// The entity is positioned where the cursor's ray intersects the plane, which allows dragging in 3D scenes.
+/// For a fixed depth, use a plane facing the camera.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component)]
+pub struct DragPlane {
+    /// Normal of the plane.
+    pub normal: Dir3,
+    /// Any point on the plane.
+    pub point: Vec3,
+}
+
+impl DragPlane {
+    pub fn new(normal: Dir3, point: Vec3) -> Self {
+        Self { normal, point }
+    }
+
+    pub(crate) fn intersect(&self, ray: Ray3d) -> Option<Vec3> {
+        ray.intersect_plane(self.point, InfinitePlane3d::new(self.normal))
+            .map(|distance| ray.get_point(distance))
+    }
+}
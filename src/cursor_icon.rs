@@ -0,0 +1,94 @@
+//! Cursor icon feedback while dragging.
+
+use bevy::{prelude::*, window::SystemCursorIcon, winit::cursor::CursorIcon};
+
+use crate::{
+    components::{Draggable, Dragging},
+    hit_test::DragHitTest,
+    receivers::validate_drop,
+};
+
+/// Resource that sets the cursor icon of the window being dragged in while it is present: `grab` over a draggable,
+/// `grabbing` while dragging, and `reject` while the dragged entity is over a receiver that is full or whose
+/// `DropValidation` would reject it. The icon the window had is restored once none of these apply.
+/// Requires the `cursor_icon` feature.
+#[derive(Resource, Clone, Copy, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct DragCursorIcons {
+    pub grab: SystemCursorIcon,
+    pub grabbing: SystemCursorIcon,
+    pub reject: SystemCursorIcon,
+}
+
+impl Default for DragCursorIcons {
+    fn default() -> Self {
+        DragCursorIcons {
+            grab: SystemCursorIcon::Grab,
+            grabbing: SystemCursorIcon::Grabbing,
+            reject: SystemCursorIcon::NotAllowed,
+        }
+    }
+}
+
+/// Sets the cursor icon of the window being dragged in according to `DragCursorIcons`, remembering the icon it had
+/// to restore it afterwards.
+pub(crate) fn update_cursor_icon(
+    mut commands: Commands,
+    icons: Res<DragCursorIcons>,
+    hit_test: DragHitTest,
+    q_draggable: Query<(Entity, &Draggable)>,
+    q_dragging: Query<(Entity, &Dragging)>,
+    q_entities: Query<EntityRef>,
+    mut saved: Local<Option<(Entity, Option<CursorIcon>)>>,
+) {
+    let drag_camera = &hit_test.drag_camera;
+    let state = match q_dragging.iter().next() {
+        Some((_, dragging)) => {
+            let rejected = q_dragging.iter().any(|(dragged, dragging)| {
+                dragging.hovering_full.is_some()
+                    || (dragging.hovering.is_some()
+                        && validate_drop(&q_entities, dragged, dragging.hovering).is_none())
+            });
+            let icon = if rejected {
+                icons.reject
+            } else {
+                icons.grabbing
+            };
+            dragging
+                .camera
+                .and_then(|camera| drag_camera.view(camera))
+                .or_else(|| {
+                    drag_camera
+                        .cursor_for(dragging.camera)
+                        .map(|(view, _)| view)
+                })
+                .map(|view| (view.window, icon))
+        }
+        None => drag_camera.cursor().and_then(|(view, _)| {
+            q_draggable
+                .iter()
+                .any(|(entity, draggable)| draggable.enabled && hit_test.is_under_cursor(entity))
+                .then_some((view.window, icons.grab))
+        }),
+    };
+    // Give the previous window its icon back when the cursor moves to another one, or nothing applies anymore
+    if let Some((window, previous)) =
+        saved.take_if(|(window, _)| state.is_none_or(|(other, _)| other != *window))
+    {
+        if let Ok(mut window) = commands.get_entity(window) {
+            window.insert(previous.unwrap_or_default());
+        }
+    }
+    let Some((window, icon)) = state else {
+        return;
+    };
+    let icon = CursorIcon::System(icon);
+    let current = q_entities
+        .get(window)
+        .ok()
+        .and_then(|window| window.get::<CursorIcon>().cloned());
+    saved.get_or_insert_with(|| (window, current.clone()));
+    if current.as_ref() != Some(&icon) {
+        commands.entity(window).insert(icon);
+    }
+}
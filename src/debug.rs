@@ -0,0 +1,161 @@
+//! Gizmo overlay for the bounds entities are hit tested with.
+
+use bevy::{prelude::*, ui::ComputedNodeTarget};
+
+use crate::{
+    components::{Draggable, Dragging, Receiver},
+    hit_test::{get_bounds, oriented_corners, Bounds, DragCameraQuery, HitTarget, SpriteSizes},
+};
+
+/// Resource that draws the hit test bounds of every `Draggable` and `Receiver` with gizmos while it is present and enabled.
+/// UI node bounds are drawn in `ui_color`, sprite and transform bounds in `world_color`, the receiver hovered by a
+/// dragged entity in `hovered_color`, and the cursor's world position in `cursor_color`.
+/// Requires the `debug` feature.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+pub struct DragDebug {
+    pub enabled: bool,
+    pub ui_color: Color,
+    pub world_color: Color,
+    pub hovered_color: Color,
+    pub cursor_color: Color,
+}
+
+impl Default for DragDebug {
+    fn default() -> Self {
+        DragDebug {
+            enabled: true,
+            ui_color: Color::srgb(0.2, 0.6, 1.0),
+            world_color: Color::srgb(1.0, 0.8, 0.2),
+            hovered_color: Color::srgb(0.2, 1.0, 0.3),
+            cursor_color: Color::srgb(1.0, 0.2, 0.2),
+        }
+    }
+}
+
+/// Entities that can be dragged or dropped on, whose bounds are drawn.
+type DebugTarget = Or<(With<Draggable>, With<Receiver>)>;
+
+pub(crate) fn draw_debug(
+    debug: Res<DragDebug>,
+    mut gizmos: Gizmos,
+    q_bounds: Query<HitTarget, DebugTarget>,
+    q_dragging: Query<&Dragging>,
+    drag_camera: DragCameraQuery,
+    sprite_sizes: SpriteSizes,
+) {
+    if !debug.enabled {
+        return;
+    }
+    for target in q_bounds.iter() {
+        let hovered = q_dragging
+            .iter()
+            .any(|dragging| dragging.hovering == Some(target.entity));
+        let Some(bounds) = get_bounds(&target, &sprite_sizes) else {
+            continue;
+        };
+        let (bounding_box, color) = match bounds {
+            Bounds::Ui(_) | Bounds::UiCircle { .. } => {
+                let bounding_box = bounds.rect();
+                let Some(view) = target
+                    .node_target
+                    .and_then(ComputedNodeTarget::camera)
+                    .and_then(|camera| drag_camera.view(camera))
+                else {
+                    continue;
+                };
+                // Gizmos are drawn in world space, so convert the UI rect's corners from physical pixels in the viewport
+                let scale_factor = view.camera.target_scaling_factor().unwrap_or(1.0);
+                let viewport_min = view
+                    .camera
+                    .physical_viewport_rect()
+                    .map_or(Vec2::ZERO, |rect| rect.min.as_vec2());
+                let to_logical = |physical: Vec2| (physical + viewport_min) / scale_factor;
+                let (Ok(min), Ok(max)) = (
+                    view.camera
+                        .viewport_to_world_2d(view.transform, to_logical(bounding_box.min)),
+                    view.camera
+                        .viewport_to_world_2d(view.transform, to_logical(bounding_box.max)),
+                ) else {
+                    continue;
+                };
+                let bounding_box = Rect::from_corners(min, max);
+                if let Bounds::UiCircle { .. } = bounds {
+                    gizmos.circle_2d(
+                        Isometry2d::from_translation(bounding_box.center()),
+                        bounding_box.width() / 2.0,
+                        if hovered {
+                            debug.hovered_color
+                        } else {
+                            debug.ui_color
+                        },
+                    );
+                    continue;
+                }
+                (bounding_box, debug.ui_color)
+            }
+            Bounds::World(bounding_box) => (bounding_box, debug.world_color),
+            Bounds::Oriented(bounding_box) => {
+                let corners = oriented_corners(target.gtransform, bounding_box);
+                gizmos.linestrip_2d(
+                    corners.into_iter().chain([corners[0]]),
+                    if hovered {
+                        debug.hovered_color
+                    } else {
+                        debug.world_color
+                    },
+                );
+                continue;
+            }
+            Bounds::Circle { center, radius } => {
+                let points = (0..=32).map(|i| {
+                    let point =
+                        center + Vec2::from_angle(i as f32 / 32.0 * std::f32::consts::TAU) * radius;
+                    target
+                        .gtransform
+                        .transform_point(point.extend(0.0))
+                        .truncate()
+                });
+                gizmos.linestrip_2d(
+                    points,
+                    if hovered {
+                        debug.hovered_color
+                    } else {
+                        debug.world_color
+                    },
+                );
+                continue;
+            }
+            Bounds::Volume(aabb) => {
+                let local = Transform::from_translation(aabb.center.into())
+                    .with_scale(Vec3::from(aabb.half_extents) * 2.0);
+                gizmos.cuboid(
+                    target.gtransform.mul_transform(local),
+                    if hovered {
+                        debug.hovered_color
+                    } else {
+                        debug.world_color
+                    },
+                );
+                continue;
+            }
+        };
+        gizmos.rect_2d(
+            Isometry2d::from_translation(bounding_box.center()),
+            bounding_box.size(),
+            if hovered { debug.hovered_color } else { color },
+        );
+    }
+
+    if let Some(world_position) = drag_camera.cursor().and_then(|(view, cursor)| {
+        view.camera
+            .viewport_to_world_2d(view.transform, cursor.logical)
+            .ok()
+    }) {
+        gizmos.circle_2d(
+            Isometry2d::from_translation(world_position),
+            4.0,
+            debug.cursor_color,
+        );
+    }
+}
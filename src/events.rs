@@ -0,0 +1,336 @@
+//! Events sent by the plugin, and the commands that control drags.
+
+use bevy::{input::mouse::MouseScrollUnit, prelude::*};
+
+use crate::{hit_test::CursorPosition, input::InputFlags};
+
+/// Event that is sent when an entity is released.
+///
+/// Exactly one `Dropped` or `DragCancelled` is sent for every `Dragged`, even when the buttons are released while
+/// the cursor is outside of the window, in which case nothing receives the entity.
+///
+/// It is also triggered for observers of the dropped entity and of the receiver.
+///
+/// It is sent in `DragSystem::Drop`, so systems ordered `.after(DragSystem::Drop)` read it in the same frame.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Dropped {
+    /// Entity that was dropped
+    pub dropped: Entity,
+    /// Entity that received the dropped entity if any. Always `None` when it was released outside of the window.
+    pub received: Option<Entity>,
+    /// Where the cursor was within the receiver's bounds, from their top left corner in `Val::Px` units for UI
+    /// receivers, and from their bottom left corner in world units otherwise. Receivers that are rotated, have a
+    /// circular hitbox or are picked with a ray measure it in their local space. `None` when nothing received the
+    /// entity, or it was dropped through `DragCommand::Drop` while the cursor was outside of the window.
+    pub received_local: Option<Vec2>,
+    /// Where the cursor was released, in logical window coordinates. `None` when it was released outside of the
+    /// window, or dropped through `DragCommand::Drop` while the cursor was outside of the window.
+    pub screen_position: Option<Vec2>,
+    /// Where the cursor was released on the 2D world plane, seen through the camera the drag followed. `None` in the
+    /// same cases as `screen_position`.
+    pub world_position: Option<Vec2>,
+    /// Index the entity was dropped at among the children of the `ReorderList` it was dropped on, or whose child it
+    /// was dropped on, not counting the entity itself. Inserting it there with `EntityCommands::insert_children`
+    /// reorders the list.
+    pub index: Option<usize>,
+    /// Entity that the receiver held and that was moved to the dropped entity's original parent to make room for it,
+    /// if the receiver has `ReceiverMode::Swap`.
+    pub displaced: Option<Entity>,
+    /// Parent the dropped entity had before it was reparented to the root while dragging, if any.
+    pub original_parent: Option<Entity>,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent when an entity has just begun being dragged.
+/// It is also triggered for observers of the dragged entity. Both happen before the entity is first moved, see
+/// `DragSystem`.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct Dragged {
+    /// Entity that is being dragged
+    pub dragged: Entity,
+    /// Where the cursor grabbed the entity, in logical window coordinates, or where it is once the wait of
+    /// `Draggable::minimum_held` ends. `None` for drags started by a `DragCommand` while the cursor was outside of the
+    /// window.
+    pub screen_position: Option<Vec2>,
+    /// Where the cursor grabbed the entity on the 2D world plane. `None` in the same cases as `screen_position`.
+    pub world_position: Option<Vec2>,
+    /// Offset from the entity's position to the point it is held at, in `Val::Px` units from the top left corner
+    /// for UI nodes, and in world units otherwise.
+    pub grab_offset: Vec3,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent when a drag is cancelled through `DragCommandsExt::cancel_drag`, released outside of the window
+/// with `OutsideRelease::Cancel`, dropped without a receiver with `DropOutside::Cancel`, or its `Draggable` is
+/// disabled, rather than the entity being dropped.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragCancelled {
+    /// Entity that was being dragged
+    pub dragged: Entity,
+    /// Parent the entity had before it was reparented to the root while dragging, if any.
+    pub original_parent: Option<Entity>,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that starts, drops or cancels a drag from user code, for input schemes other than the built in mouse handling.
+/// These are usually sent through `DragCommandsExt`.
+///
+/// Drags started this way are never dropped by releasing mouse buttons, only by a `Drop` or `Cancel` command.
+#[derive(Event, Clone, Copy, Debug)]
+pub enum DragCommand {
+    /// Starts dragging a `Draggable` entity, held as set by `DragSettings::default_grab_mode`, sending `Dragged`.
+    Start(Entity),
+    /// Drops a dragged entity onto the receiver it is hovering, if any, sending `Dropped`.
+    Drop(Entity),
+    /// Ends a drag without dropping the entity, sending `DragCancelled`.
+    Cancel(Entity),
+}
+
+/// Extension trait for `Commands` to control drags from user code, see `DragCommand`.
+pub trait DragCommandsExt {
+    /// Starts dragging a `Draggable` entity.
+    fn start_drag(&mut self, entity: Entity);
+    /// Drops a dragged entity onto the receiver it is hovering, if any.
+    fn drop_drag(&mut self, entity: Entity);
+    /// Ends a drag without dropping the entity.
+    fn cancel_drag(&mut self, entity: Entity);
+}
+
+impl DragCommandsExt for Commands<'_, '_> {
+    fn start_drag(&mut self, entity: Entity) {
+        self.send_event(DragCommand::Start(entity));
+    }
+
+    fn drop_drag(&mut self, entity: Entity) {
+        self.send_event(DragCommand::Drop(entity));
+    }
+
+    fn cancel_drag(&mut self, entity: Entity) {
+        self.send_event(DragCommand::Cancel(entity));
+    }
+}
+
+/// Event that is sent when the modifiers held change while an entity is being dragged, for example to switch
+/// between moving and copying. Clicks aren't reported, as releasing them drops the entity instead.
+/// It is also triggered for observers of the dragged entity.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragInputChanged {
+    /// Entity that is being dragged
+    pub dragged: Entity,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+    /// Inputs at the time the drag started or the modifiers last changed
+    pub previous: InputFlags,
+}
+
+/// Event that is sent once a dragged entity has hovered over the same receiver for the duration of its `DwellTrigger`,
+/// for example to open a folder. It is sent again if the entity leaves and comes back, and every
+/// `DwellTrigger::repeat` seconds while it stays if that is set.
+/// It is also triggered for observers of the dragged entity and of the receiver.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HoverDwell {
+    /// Entity that is being dragged
+    pub dragged: Entity,
+    /// Receiver that was hovered over
+    pub receiver: Entity,
+    /// Time the entity has hovered over the receiver for in seconds
+    pub elapsed: f64,
+}
+
+/// Event that is sent when a click lands on a `Draggable` but doesn't start dragging it, to explain why while debugging.
+/// Only sent on the frame a mouse button is pressed. Requires the `debug` feature.
+#[cfg(feature = "debug")]
+#[derive(Event, Debug)]
+pub struct DragStartFailed {
+    /// Entity that was clicked on
+    pub draggable: Entity,
+    /// Why the entity isn't being dragged
+    pub reason: DragStartFailure,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Reason a click on a `Draggable` didn't start dragging it, sent in `DragStartFailed`.
+/// Requires the `debug` feature.
+#[cfg(feature = "debug")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DragStartFailure {
+    /// `Draggable::enabled` is false.
+    Disabled,
+    /// Another entity is already being dragged or waiting for its `minimum_held` time.
+    DragActive,
+    /// Inputs in `Draggable::required` that weren't held.
+    RequiredMissing(InputFlags),
+    /// Inputs in `Draggable::disallowed` that were held.
+    DisallowedHeld(InputFlags),
+}
+
+/// Event that is sent for every dragged entity when the mouse wheel is scrolled during a drag, for example to rotate or
+/// resize the held entity. Scrolling while nothing is dragged sends nothing, so it doesn't conflict with normal scrolling.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragScroll {
+    /// Entity that is being dragged
+    pub dragged: Entity,
+    /// Distance scrolled along both axes, with positive y scrolling up and positive x scrolling right
+    pub delta: Vec2,
+    /// Whether `delta` is in lines or pixels, depending on the device
+    pub unit: MouseScrollUnit,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent when a dragged entity with `DragNudge` is moved with the arrow keys or a gamepad.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragNudged {
+    /// Entity that is being dragged
+    pub dragged: Entity,
+    /// How far the entity was moved this frame, with positive y pointing up
+    pub delta: Vec2,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent every frame a dragged entity moves, for example to tilt it in the direction it is moving.
+/// Nothing is sent while it stays still.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragMoved {
+    /// Entity that is being dragged
+    pub dragged: Entity,
+    /// Where the entity was moved to. For UI nodes, this is their `left` and `top` in `Val::Px` units, with z left
+    /// at zero. Otherwise, it is their translation.
+    pub position: Vec3,
+    /// How far the entity moved since the previous frame, in the same units as `position`.
+    pub delta: Vec3,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent every frame an entity is dragged while the cursor is in a window, with how far the cursor moved
+/// since the previous frame. Unlike `DragMoved`, this follows the cursor rather than the entity, which makes it suited
+/// to moving entities whose `Draggable::follow_cursor` is off, such as with physics.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragMove {
+    /// Entity that is being dragged
+    pub dragged: Entity,
+    /// How far the cursor moved on the 2D world plane since the previous frame
+    pub world_delta: Vec2,
+    /// How far the cursor moved in logical window coordinates since the previous frame
+    pub logical_delta: Vec2,
+    /// Where the cursor is, in logical window coordinates
+    pub cursor: Vec2,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent when an entity is waiting for a minimum time to elapse to initiate dragging.
+/// It is also triggered for observers of the entity.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragAwait {
+    /// Entity that is awaiting to be dragged
+    pub awaiting: Entity,
+    /// Where the cursor pressed on the entity, in logical window coordinates.
+    pub screen_position: Vec2,
+    /// Where the cursor pressed on the entity on the 2D world plane.
+    pub world_position: Vec2,
+    /// Offset from the entity's position to the point it will be held at, as in `Dragged`.
+    pub grab_offset: Vec3,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent when an entity that was waiting to be dragged won't be, because the inputs stopped matching its
+/// `Draggable` or the cursor left it before the wait ended. Useful to reset a hold progress indicator started on
+/// `DragAwait`. It is also triggered for observers of the entity.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DragAwaitCancelled {
+    /// Entity that was awaiting to be dragged
+    pub awaiting: Entity,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent when an entity is hovered over a new receiver, and when it is dropped.
+/// It is also triggered for observers of the dragged entity and of both receivers.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HoveredChange {
+    /// The entity that is being dragged
+    pub hovered: Entity,
+    /// The entity that is now being hovered over, None if no receivers are being hovered over or if it has been dropped.
+    pub receiver: Option<Entity>,
+    /// The last entity that was being hovered over if any
+    pub prevreceiver: Option<Entity>,
+    /// Where the cursor is, in logical window coordinates. `None` when it is outside of the window, such as when the
+    /// entity was released there.
+    pub screen_position: Option<Vec2>,
+    /// Where the cursor is on the 2D world plane. `None` in the same cases as `screen_position`.
+    pub world_position: Option<Vec2>,
+    /// Where the entity is over the new receiver, measured like `Dropped::received_local`. `None` if there is no new
+    /// receiver.
+    pub receiver_local: Option<Vec2>,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+impl HoveredChange {
+    /// Event for an entity no longer hovering over any receiver.
+    pub(crate) fn left(
+        hovered: Entity,
+        prevreceiver: Option<Entity>,
+        cursor: Option<&CursorPosition>,
+        inputs: InputFlags,
+    ) -> Self {
+        HoveredChange {
+            hovered,
+            receiver: None,
+            prevreceiver,
+            screen_position: cursor.map(|cursor| cursor.logical),
+            world_position: cursor.map(|cursor| cursor.world()),
+            receiver_local: None,
+            inputs,
+        }
+    }
+}
+
+/// Event that is sent when a dragged entity starts hovering over a receiver.
+/// It is also triggered for observers of the dragged entity and of the receiver.
+///
+/// Moving straight from one receiver to another sends a `HoverExit` for the first before the `HoverEnter` for the
+/// second.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HoverEnter {
+    /// The entity that is being dragged
+    pub dragged: Entity,
+    /// The receiver that is now being hovered over
+    pub receiver: Entity,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent when the place a dragged entity would be inserted at in a `ReorderList` changes, including when
+/// it starts hovering over the list, for example to draw an insertion line.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ReorderHover {
+    /// The entity that is being dragged
+    pub dragged: Entity,
+    /// The list that is being hovered over
+    pub container: Entity,
+    /// Index among the list's children, not counting the dragged entity, that it would be inserted at
+    pub index: usize,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
+
+/// Event that is sent when a dragged entity stops hovering over a receiver, including when it is dropped or cancelled.
+/// It is also triggered for observers of the dragged entity and of the receiver.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HoverExit {
+    /// The entity that is being dragged
+    pub dragged: Entity,
+    /// The receiver that is no longer being hovered over
+    pub receiver: Entity,
+    /// Inputs at the time of the event being sent
+    pub inputs: InputFlags,
+}
@@ -0,0 +1,703 @@
+//! Finding the entities under the cursor, through the camera it is seen through.
+
+use bevy::{
+    ecs::{query::QueryData, system::SystemParam},
+    platform::collections::HashMap,
+    prelude::*,
+    render::{camera::NormalizedRenderTarget, primitives::Aabb},
+    ui::{CalculatedClip, ComputedNodeTarget},
+    window::PrimaryWindow,
+};
+
+use crate::{
+    components::{DragHitbox, DragOffset, DragPlane, GrabMode, PixelPerfect},
+    input::DragInputState,
+};
+
+/// Component that marks the camera used for hit testing and positioning dragged entities, for apps with several cameras
+/// rendering to the same window.
+///
+/// Every window is dragged in through its own camera, found among the cameras that render to it. Without this
+/// component, the camera marked with `IsDefaultUiCamera` is used, and otherwise the only `Camera2d` or the only camera.
+/// If none of these can be found, dragging in that window is disabled and an error is logged.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct DragCamera;
+
+/// A camera used for dragging and the window it renders to.
+#[derive(Clone, Copy)]
+pub(crate) struct DragView<'a> {
+    pub(crate) entity: Entity,
+    pub(crate) window: Entity,
+    pub(crate) camera: &'a Camera,
+    pub(crate) transform: &'a GlobalTransform,
+}
+
+/// A camera, along with what decides whether it is used for dragging.
+type CameraData = (
+    Entity,
+    &'static Camera,
+    &'static GlobalTransform,
+    Has<DragCamera>,
+    Has<IsDefaultUiCamera>,
+    Has<Camera2d>,
+);
+
+/// Finds the camera used for dragging in each window, see `DragCamera`.
+#[derive(SystemParam)]
+pub(crate) struct DragCameraQuery<'w, 's> {
+    q_cameras: Query<'w, 's, CameraData>,
+    q_primary_window: Option<Single<'w, Entity, With<PrimaryWindow>>>,
+    pub(crate) input: Res<'w, DragInputState>,
+}
+
+impl DragCameraQuery<'_, '_> {
+    /// Window the camera renders to, if it renders to a window.
+    fn window_of(&self, camera: &Camera) -> Option<Entity> {
+        let primary_window = self.q_primary_window.as_deref().copied();
+        match camera.target.normalize(primary_window)? {
+            NormalizedRenderTarget::Window(window) => Some(window.entity()),
+            _ => None,
+        }
+    }
+
+    /// Camera used for dragging in the given window.
+    fn view_in(&self, window: Entity) -> Option<DragView<'_>> {
+        let cameras = || {
+            self.q_cameras
+                .iter()
+                .filter(move |(_, camera, ..)| self.window_of(camera) == Some(window))
+        };
+        let found = cameras()
+            .find(|(_, _, _, drag_camera, ..)| *drag_camera)
+            .or_else(|| cameras().find(|(_, _, _, _, default_ui, _)| *default_ui))
+            .or_else(|| single(cameras().filter(|(.., camera_2d)| *camera_2d)))
+            .or_else(|| single(cameras()));
+        #[cfg(feature = "log")]
+        if found.is_none() && cameras().next().is_some() {
+            error_once!("could not choose a camera for dragging, add DragCamera to one of the cameras of the window");
+        }
+        found.map(|(entity, camera, transform, ..)| DragView {
+            entity,
+            window,
+            camera,
+            transform,
+        })
+    }
+
+    /// The given camera, if it renders to a window.
+    pub(crate) fn view(&self, camera: Entity) -> Option<DragView<'_>> {
+        let (entity, camera, transform, ..) = self.q_cameras.get(camera).ok()?;
+        Some(DragView {
+            entity,
+            window: self.window_of(camera)?,
+            camera,
+            transform,
+        })
+    }
+
+    /// The cursor in whichever window it is over, along with that window's drag camera. See `DragInputState`.
+    pub(crate) fn cursor(&self) -> Option<(DragView<'_>, CursorPosition)> {
+        let (window, logical) = self.input.cursor?;
+        let view = self.view_in(window)?;
+        Some((view, CursorPosition::from_logical(logical, &view)?))
+    }
+
+    /// The cursor in the window of the given camera, or in whichever window it is over without one.
+    pub(crate) fn cursor_for(
+        &self,
+        camera: Option<Entity>,
+    ) -> Option<(DragView<'_>, CursorPosition)> {
+        let Some(camera) = camera else {
+            return self.cursor();
+        };
+        let view = self.view(camera)?;
+        let (_, logical) = self
+            .input
+            .cursor
+            .filter(|(window, _)| *window == view.window)?;
+        Some((view, CursorPosition::from_logical(logical, &view)?))
+    }
+}
+
+/// The only item of the iterator, if it has exactly one.
+pub(crate) fn single<T>(mut iter: impl Iterator<Item = T>) -> Option<T> {
+    let first = iter.next()?;
+    iter.next().is_none().then_some(first)
+}
+
+/// System parameter that hit tests entities the same way dragging does, for use in your own systems.
+/// It handles UI nodes, sprites, rotated entities, `DragHitbox` and 3D entities with an `Aabb` alike.
+#[derive(SystemParam)]
+pub struct DragHitTest<'w, 's> {
+    q_targets: Query<'w, 's, HitTarget>,
+    pub(crate) drag_camera: DragCameraQuery<'w, 's>,
+    sprite_sizes: SpriteSizes<'w>,
+}
+
+impl DragHitTest<'_, '_> {
+    /// Whether the cursor is over the entity, in whichever window it is over.
+    pub fn is_under_cursor(&self, entity: Entity) -> bool {
+        self.drag_camera
+            .cursor()
+            .is_some_and(|(_, cursor)| self.is_hit(entity, &cursor))
+    }
+
+    /// Whether the given position in logical window coordinates is over the entity, when seen through the camera.
+    pub fn is_at(&self, entity: Entity, camera: Entity, logical_position: Vec2) -> bool {
+        self.drag_camera
+            .view(camera)
+            .and_then(|view| CursorPosition::from_logical(logical_position, &view))
+            .is_some_and(|cursor| self.is_hit(entity, &cursor))
+    }
+
+    /// Whether the cursor is over the entity in the camera's window.
+    pub(crate) fn is_under_cursor_for(&self, entity: Entity, camera: Entity) -> bool {
+        self.drag_camera
+            .cursor_for(Some(camera))
+            .is_some_and(|(_, cursor)| self.is_hit(entity, &cursor))
+    }
+
+    fn is_hit(&self, entity: Entity, cursor: &CursorPosition) -> bool {
+        self.q_targets
+            .get(entity)
+            .is_ok_and(|target| is_in_bounds(&target, &self.sprite_sizes, cursor))
+    }
+}
+
+/// Components used to test whether the cursor is over an entity.
+#[derive(QueryData)]
+pub(crate) struct HitTarget {
+    pub(crate) entity: Entity,
+    pub(crate) gtransform: &'static GlobalTransform,
+    sprite: Option<&'static Sprite>,
+    pub(crate) computed_node: Option<&'static ComputedNode>,
+    pub(crate) node_target: Option<&'static ComputedNodeTarget>,
+    aabb: Option<&'static Aabb>,
+    hitbox: Option<&'static DragHitbox>,
+    pixel_perfect: Option<&'static PixelPerfect>,
+    mesh_2d: Has<Mesh2d>,
+    text_2d: Has<Text2d>,
+    pub(crate) clip: Option<&'static CalculatedClip>,
+}
+
+/// Order entities are drawn in, to grab the one on top.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct DrawOrder {
+    /// Stack index of UI nodes, which are drawn above everything in the world.
+    pub(crate) ui: Option<u32>,
+    /// Distance along the cursor's ray to the entity, see `Bounds::distance`.
+    pub(crate) distance: f32,
+}
+
+impl DrawOrder {
+    pub(crate) fn new(
+        target: &HitTargetItem,
+        sprite_sizes: &SpriteSizes,
+        cursor: &CursorPosition,
+    ) -> Self {
+        DrawOrder {
+            ui: target.computed_node.map(ComputedNode::stack_index),
+            // Draggables grabbed through their handles may have no bounds of their own
+            distance: get_bounds(target, sprite_sizes).map_or_else(
+                || plane_distance(target.gtransform, cursor),
+                |bounds| bounds.distance(target.gtransform, cursor),
+            ),
+        }
+    }
+
+    pub(crate) fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // The nearest entity is drawn on top
+        self.ui
+            .cmp(&other.ui)
+            .then(other.distance.total_cmp(&self.distance))
+    }
+}
+
+/// Chooses the draggable to grab among the candidates under the cursor, given the ancestors of each.
+///
+/// Draggables nested in another are grabbed instead of their ancestors, as Z isn't meaningful between them. Among the
+/// rest, the one drawn on top is grabbed, breaking ties by the highest entity like `ReceiverIndex::receivers_at` so
+/// the choice doesn't depend on query order.
+pub(crate) fn select_candidate<T>(
+    mut candidates: Vec<(Entity, DrawOrder, T)>,
+    ancestors_of: impl Fn(Entity) -> Vec<Entity>,
+) -> Option<(Entity, T)> {
+    let ancestors: Vec<Entity> = candidates
+        .iter()
+        .flat_map(|(entity, ..)| ancestors_of(*entity))
+        .collect();
+    candidates.retain(|(entity, ..)| !ancestors.contains(entity));
+    candidates
+        .into_iter()
+        .max_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(&b.0)))
+        .map(|(entity, _, candidate)| (entity, candidate))
+}
+
+/// Position of the cursor in each space that entities are hit tested in.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct CursorPosition {
+    /// Logical window coordinates.
+    pub(crate) logical: Vec2,
+    /// Physical coordinates from the top left corner of the camera's viewport, which UI nodes are laid out in.
+    pub(crate) physical: Vec2,
+    /// Ray cast from the camera through the cursor.
+    pub(crate) ray: Ray3d,
+    /// Camera the cursor is seen through. Only UI nodes rendered by this camera can be under the cursor.
+    pub(crate) camera: Entity,
+}
+
+impl CursorPosition {
+    pub(crate) fn from_logical(logical: Vec2, view: &DragView) -> Option<Self> {
+        // Nothing rendered by the camera is under the cursor while it is outside of its viewport, such as over
+        // the bars around a letterboxed viewport
+        if !view.camera.logical_viewport_rect()?.contains(logical) {
+            return None;
+        }
+        let ray = view
+            .camera
+            .viewport_to_world(view.transform, logical)
+            .ok()?;
+        let viewport_min = view.camera.physical_viewport_rect()?.min.as_vec2();
+        Some(Self {
+            logical,
+            physical: logical * view.camera.target_scaling_factor().unwrap_or(1.0) - viewport_min,
+            ray,
+            camera: view.entity,
+        })
+    }
+
+    /// Position a cursor would have to be at to be over the given world position.
+    pub(crate) fn from_world(world: Vec3, view: &DragView) -> Option<Self> {
+        let logical = view.camera.world_to_viewport(view.transform, world).ok()?;
+        Self::from_logical(logical, view)
+    }
+
+    /// Cursor position on the 2D world plane.
+    pub(crate) fn world(&self) -> Vec2 {
+        self.ray.origin.truncate()
+    }
+}
+
+/// Where in its image a `PixelPerfect` sprite is sampled.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PixelTest {
+    image: AssetId<Image>,
+    /// Rectangle the sprite covers in its local space.
+    local: Rect,
+    /// Texels of the image the sprite displays.
+    texels: Rect,
+    flip_x: bool,
+    flip_y: bool,
+    alpha_threshold: f32,
+}
+
+impl PixelTest {
+    pub(crate) fn new(target: &HitTargetItem, sprite_sizes: &SpriteSizes) -> Option<Self> {
+        let (Some(pixel_perfect), Some(sprite), None) =
+            (target.pixel_perfect, target.sprite, target.hitbox)
+        else {
+            return None;
+        };
+        let size = sprite_sizes.sprite(sprite)?;
+        let texels = match (&sprite.texture_atlas, sprite.rect) {
+            (Some(atlas), _) => atlas.texture_rect(&sprite_sizes.atlases)?.as_rect(),
+            (None, Some(rect)) => rect,
+            (None, None) => Rect::from_corners(Vec2::ZERO, sprite_sizes.get(sprite.image.id())?),
+        };
+        Some(PixelTest {
+            image: sprite.image.id(),
+            local: Rect::from_center_size(-sprite.anchor.as_vec() * size, size),
+            texels,
+            flip_x: sprite.flip_x,
+            flip_y: sprite.flip_y,
+            alpha_threshold: pixel_perfect.alpha_threshold,
+        })
+    }
+
+    pub(crate) fn contains(
+        &self,
+        gtransform: &GlobalTransform,
+        cursor: &CursorPosition,
+        images: &Assets<Image>,
+    ) -> bool {
+        let Some(image) = images.get(self.image) else {
+            return true;
+        };
+        // Images have their origin at the top left, while the sprite's local space points up
+        let mut uv = (local_cursor(gtransform, cursor) - self.local.min) / self.local.size();
+        uv.y = 1.0 - uv.y;
+        if self.flip_x {
+            uv.x = 1.0 - uv.x;
+        }
+        if self.flip_y {
+            uv.y = 1.0 - uv.y;
+        }
+        let texel = (self.texels.min + uv * self.texels.size())
+            .clamp(self.texels.min, self.texels.max - 1.0);
+        image
+            .get_color_at(texel.x as u32, texel.y as u32)
+            .map_or(true, |color| color.alpha() > self.alpha_threshold)
+    }
+}
+
+/// Resource caching the pixel size of every loaded image, so hit testing sprites doesn't need to look up the image itself.
+/// Kept up to date from `AssetEvent<Image>`, including when an image is hot reloaded at a different size.
+#[derive(Resource, Default)]
+pub(crate) struct SpriteSizeCache(HashMap<AssetId<Image>, Vec2>);
+
+/// Looks up the pixel size of sprite images through the `SpriteSizeCache`.
+#[derive(SystemParam)]
+pub(crate) struct SpriteSizes<'w> {
+    cache: Res<'w, SpriteSizeCache>,
+    pub(crate) assets: Res<'w, Assets<Image>>,
+    atlases: Res<'w, Assets<TextureAtlasLayout>>,
+}
+
+impl SpriteSizes<'_> {
+    fn get(&self, id: AssetId<Image>) -> Option<Vec2> {
+        // Images loaded this frame haven't had their events sent yet, so aren't cached
+        self.cache
+            .0
+            .get(&id)
+            .copied()
+            .or_else(|| self.assets.get(id).map(|image| image.size().as_vec2()))
+    }
+
+    /// Size the sprite is rendered at before scaling, or `None` if its image or atlas layout is still loading.
+    fn sprite(&self, sprite: &Sprite) -> Option<Vec2> {
+        if let Some(custom_size) = sprite.custom_size {
+            return Some(custom_size);
+        }
+        // Sprites from a sheet are as big as their current frame
+        if let Some(atlas) = &sprite.texture_atlas {
+            return atlas
+                .texture_rect(&self.atlases)
+                .map(|rect| rect.size().as_vec2());
+        }
+        self.get(sprite.image.id())
+    }
+}
+
+pub(crate) fn update_sprite_size_cache(
+    mut cache: ResMut<SpriteSizeCache>,
+    mut er_images: EventReader<AssetEvent<Image>>,
+    assets: Res<Assets<Image>>,
+) {
+    for event in er_images.read() {
+        match *event {
+            AssetEvent::Added { id }
+            | AssetEvent::Modified { id }
+            | AssetEvent::LoadedWithDependencies { id } => {
+                if let Some(image) = assets.get(id) {
+                    cache.0.insert(id, image.size().as_vec2());
+                }
+            }
+            AssetEvent::Removed { id } | AssetEvent::Unused { id } => {
+                cache.0.remove(&id);
+            }
+        }
+    }
+}
+
+/// Region of an entity that the cursor is tested against.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Bounds {
+    /// Rectangle in physical window coordinates, used for UI nodes.
+    Ui(Rect),
+    /// Rectangle in 2D world coordinates, used for sprites and plain transforms.
+    World(Rect),
+    /// Rectangle in the entity's local space, used for rotated sprites and plain transforms.
+    Oriented(Rect),
+    /// Circle in physical window coordinates, used for UI nodes with a circular hitbox.
+    UiCircle { center: Vec2, radius: f32 },
+    /// Circle in the entity's local space, used for circular hitboxes.
+    Circle { center: Vec2, radius: f32 },
+    /// Box in the entity's local space, tested against the cursor's ray.
+    Volume(Aabb),
+}
+
+/// Bounds of the entity, or `None` if they can't be known yet, such as for a sprite whose image is still loading.
+pub(crate) fn get_bounds(target: &HitTargetItem, sprite_sizes: &SpriteSizes) -> Option<Bounds> {
+    let gtransform = target.gtransform;
+    if let Some(hitbox) = target.hitbox {
+        let translation = gtransform.translation().truncate();
+        // UI hitboxes are given in the same units as `Val::Px`, while nodes are laid out in physical pixels
+        let ui_scale = target
+            .computed_node
+            .map(|computed_node| computed_node.inverse_scale_factor().recip());
+        match *hitbox {
+            DragHitbox::Rect { size, offset } => {
+                if let Some(ui_scale) = ui_scale {
+                    return Some(Bounds::Ui(Rect::from_center_size(
+                        translation + offset * ui_scale,
+                        size * ui_scale,
+                    )));
+                }
+                let transform = gtransform.compute_transform();
+                if !transform.rotation.is_near_identity() {
+                    let scale = transform.scale.truncate();
+                    return Some(Bounds::Oriented(Rect::from_center_size(
+                        offset / scale,
+                        size / scale,
+                    )));
+                }
+                Some(Bounds::World(Rect::from_center_size(
+                    translation + offset,
+                    size,
+                )))
+            }
+            DragHitbox::Circle { radius, offset } => match ui_scale {
+                Some(ui_scale) => Some(Bounds::UiCircle {
+                    center: translation + offset * ui_scale,
+                    radius: radius * ui_scale,
+                }),
+                None => Some(Bounds::Circle {
+                    center: offset,
+                    radius,
+                }),
+            },
+            DragHitbox::Absolute { rect } => match ui_scale {
+                Some(ui_scale) => Some(Bounds::Ui(Rect::from_corners(
+                    rect.min * ui_scale,
+                    rect.max * ui_scale,
+                ))),
+                None => Some(Bounds::World(rect)),
+            },
+        }
+    } else if let Some(computed_node) = target.computed_node {
+        let bounding_box =
+            Rect::from_center_size(gtransform.translation().truncate(), computed_node.size());
+        Some(Bounds::Ui(bounding_box))
+    } else if let (true, Some(aabb)) = (target.mesh_2d || target.text_2d, target.aabb) {
+        // 2D meshes and text are flat, so their box is tested as a rectangle on the 2D world plane.
+        // Bevy keeps the box of Text2d in sync with its laid out text, anchor included.
+        let local =
+            Rect::from_center_half_size(aabb.center.truncate(), aabb.half_extents.truncate());
+        let transform = gtransform.compute_transform();
+        if !transform.rotation.is_near_identity() {
+            return Some(Bounds::Oriented(local));
+        }
+        let scale = transform.scale.truncate();
+        let center = transform.translation.truncate() + local.center() * scale;
+        Some(Bounds::World(Rect::from_center_size(
+            center,
+            local.size() * scale.abs(),
+        )))
+    } else if let (None, Some(aabb)) = (target.sprite, target.aabb) {
+        Some(Bounds::Volume(*aabb))
+    } else {
+        let transform = gtransform.compute_transform();
+        // Rotated entities are tested in their local space, where they are axis aligned
+        if !transform.rotation.is_near_identity() {
+            let size = target
+                .sprite
+                .map_or(Some(Vec2::ONE), |img| sprite_sizes.sprite(img))?;
+            let center = target
+                .sprite
+                .map_or(Vec2::ZERO, |img| -img.anchor.as_vec() * size);
+            return Some(Bounds::Oriented(Rect::from_center_size(center, size)));
+        }
+        let mut scaled_image_dimension = transform.scale.truncate();
+        let mut center = gtransform.translation().truncate();
+
+        //Need to account for sprite size if it is a sprite.
+        if let Some(img) = target.sprite {
+            scaled_image_dimension *= sprite_sizes.sprite(img)?;
+            // The sprite's anchor is the point placed at its translation, rather than its center
+            center -= img.anchor.as_vec() * scaled_image_dimension;
+        }
+
+        let bounding_box = Rect::from_center_size(center, scaled_image_dimension);
+        Some(Bounds::World(bounding_box))
+    }
+}
+
+impl Bounds {
+    /// Rectangle around the bounds, in the space they are in.
+    pub(crate) fn rect(&self) -> Rect {
+        match *self {
+            Bounds::Ui(rect) | Bounds::World(rect) | Bounds::Oriented(rect) => rect,
+            Bounds::UiCircle { center, radius } | Bounds::Circle { center, radius } => {
+                Rect::from_center_size(center, Vec2::splat(radius * 2.0))
+            }
+            Bounds::Volume(aabb) => {
+                Rect::from_center_half_size(aabb.center.truncate(), aabb.half_extents.truncate())
+            }
+        }
+    }
+
+    pub(crate) fn contains(&self, gtransform: &GlobalTransform, cursor: &CursorPosition) -> bool {
+        match self {
+            Bounds::Ui(bounding_box) => bounding_box.contains(cursor.physical),
+            Bounds::World(bounding_box) => bounding_box.contains(cursor.world()),
+            Bounds::Oriented(bounding_box) => {
+                bounding_box.contains(local_cursor(gtransform, cursor))
+            }
+            Bounds::UiCircle { center, radius } => cursor.physical.distance(*center) <= *radius,
+            Bounds::Circle { center, radius } => {
+                local_cursor(gtransform, cursor).distance(*center) <= *radius
+            }
+            Bounds::Volume(aabb) => ray_aabb_distance(gtransform, aabb, cursor.ray).is_some(),
+        }
+    }
+
+    /// Distance along the cursor's ray to where it hits the bounds, smallest for the ones drawn on top.
+    ///
+    /// Flat bounds are as far as the plane they are on, which orders them by Z. UI is drawn above the world,
+    /// and volumes are as far as the nearest point the ray hits on their box.
+    pub(crate) fn distance(&self, gtransform: &GlobalTransform, cursor: &CursorPosition) -> f32 {
+        match self {
+            Bounds::Ui(_) | Bounds::UiCircle { .. } => f32::NEG_INFINITY,
+            Bounds::World(_) | Bounds::Oriented(_) | Bounds::Circle { .. } => {
+                plane_distance(gtransform, cursor)
+            }
+            Bounds::Volume(aabb) => {
+                ray_aabb_distance(gtransform, aabb, cursor.ray).unwrap_or(f32::INFINITY)
+            }
+        }
+    }
+}
+
+/// Distance from the cursor down to the entity's plane on the 2D world plane, the way flat entities are hit tested.
+pub(crate) fn plane_distance(gtransform: &GlobalTransform, cursor: &CursorPosition) -> f32 {
+    cursor.ray.origin.z - gtransform.translation().z
+}
+
+/// Cursor position on the 2D world plane, in the entity's local space.
+pub(crate) fn local_cursor(gtransform: &GlobalTransform, cursor: &CursorPosition) -> Vec2 {
+    let world = cursor.world().extend(gtransform.translation().z);
+    gtransform
+        .affine()
+        .inverse()
+        .transform_point3(world)
+        .truncate()
+}
+
+/// World positions of the corners of a rectangle in the entity's local space, in order around it.
+pub(crate) fn oriented_corners(gtransform: &GlobalTransform, bounding_box: Rect) -> [Vec2; 4] {
+    [
+        bounding_box.min,
+        Vec2::new(bounding_box.max.x, bounding_box.min.y),
+        bounding_box.max,
+        Vec2::new(bounding_box.min.x, bounding_box.max.y),
+    ]
+    .map(|corner| gtransform.transform_point(corner.extend(0.0)).truncate())
+}
+
+pub(crate) fn is_in_bounds(
+    target: &HitTargetItem,
+    sprite_sizes: &SpriteSizes,
+    cursor: &CursorPosition,
+) -> bool {
+    // UI nodes can only be under the cursor of the window their camera renders to
+    let same_camera = target
+        .node_target
+        .is_none_or(|node_target| node_target.camera() == Some(cursor.camera));
+    // Parts of UI nodes scrolled out of a container with clipped overflow are hidden, and can't be hit
+    let unclipped = target
+        .clip
+        .is_none_or(|clip| clip.clip.contains(cursor.physical));
+    same_camera
+        && unclipped
+        && get_bounds(target, sprite_sizes)
+            .is_some_and(|bounds| bounds.contains(target.gtransform, cursor))
+        && PixelTest::new(target, sprite_sizes).is_none_or(|pixel_test| {
+            pixel_test.contains(target.gtransform, cursor, &sprite_sizes.assets)
+        })
+}
+
+/// Converts a `DragOffset` in logical pixels into the world units between the cursor and the point the entity is
+/// held at, measured on the 2D world plane or along the `DragPlane`.
+pub(crate) fn world_drag_offset(
+    drag_offset: &DragOffset,
+    view: &DragView,
+    cursor: &CursorPosition,
+    drag_plane: Option<&DragPlane>,
+) -> Vec3 {
+    let held = cursor.logical - Vec2::new(drag_offset.x, drag_offset.y);
+    let Ok(held_ray) = view.camera.viewport_to_world(view.transform, held) else {
+        return Vec3::ZERO;
+    };
+    match drag_plane {
+        Some(drag_plane) => match (
+            drag_plane.intersect(cursor.ray),
+            drag_plane.intersect(held_ray),
+        ) {
+            (Some(cursor_hit), Some(held_hit)) => cursor_hit - held_hit,
+            _ => Vec3::ZERO,
+        },
+        None => (cursor.world() - held_ray.origin.truncate()).extend(0.0),
+    }
+}
+
+/// Distance along the ray to where it enters the box, if it hits the box at all.
+pub(crate) fn ray_aabb_distance(
+    gtransform: &GlobalTransform,
+    aabb: &Aabb,
+    ray: Ray3d,
+) -> Option<f32> {
+    // Test in the entity's local space so rotation and scale are accounted for
+    let world_to_local = gtransform.affine().inverse();
+    let origin = world_to_local.transform_point3(ray.origin);
+    let direction = world_to_local.transform_vector3(*ray.direction);
+
+    let near = (Vec3::from(aabb.min()) - origin) / direction;
+    let far = (Vec3::from(aabb.max()) - origin) / direction;
+    let enter = near.min(far).max_element();
+    let exit = near.max(far).min_element();
+    (enter <= exit && exit >= 0.0).then_some(enter.max(0.0))
+}
+
+pub(crate) fn get_grab_offset(
+    mode: GrabMode,
+    target: &HitTargetItem,
+    drag_plane: Option<&DragPlane>,
+    cursor: &CursorPosition,
+) -> Vec3 {
+    let translation = target.gtransform.translation();
+    if let Some(computed_node) = target.computed_node {
+        // UI nodes are positioned by their top left corner, in the same units as `Val::Px` rather than the physical
+        // pixels they are laid out in
+        let half_size = computed_node.size() / 2.0;
+        let offset = match mode {
+            GrabMode::Center => half_size,
+            GrabMode::Cursor => cursor.physical - (translation.truncate() - half_size),
+        };
+        (offset * computed_node.inverse_scale_factor()).extend(0.0)
+    } else if let Some(drag_plane) = drag_plane {
+        match (mode, drag_plane.intersect(cursor.ray)) {
+            (GrabMode::Cursor, Some(hit)) => hit - translation,
+            _ => Vec3::ZERO,
+        }
+    } else {
+        match mode {
+            GrabMode::Center => Vec3::ZERO,
+            GrabMode::Cursor => (cursor.world() - translation.truncate()).extend(0.0),
+        }
+    }
+}
+
+/// `DragOffset` holding the entity at the point the cursor is over, for `Draggable::insert_offset`.
+pub(crate) fn get_drag_offset(
+    target: &HitTargetItem,
+    view: &DragView,
+    cursor: &CursorPosition,
+) -> Option<DragOffset> {
+    let translation = target.gtransform.translation();
+    if let Some(computed_node) = target.computed_node {
+        // Measured in physical pixels like the layout, then converted to `Val::Px` units
+        let top_left = translation.truncate() - computed_node.size() / 2.0;
+        let scale = computed_node.inverse_scale_factor();
+        Some(DragOffset::from_grab(
+            top_left * scale,
+            cursor.physical * scale,
+        ))
+    } else {
+        let entity_pos = view
+            .camera
+            .world_to_viewport(view.transform, translation)
+            .ok()?;
+        Some(DragOffset::from_grab(entity_pos, cursor.logical))
+    }
+}
@@ -0,0 +1,285 @@
+//! Inputs that start and end drags, and how they are read from the mouse and keyboard.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bitflags::bitflags;
+use serde::{Deserialize, Serialize};
+use std::ops::Mul;
+
+bitflags! {
+    #[derive(Clone,Copy,Debug,Default,PartialEq,Eq,Reflect,Serialize,Deserialize)]
+    #[reflect(opaque, Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(transparent)]
+    /// Flags that keep track of relevant inputs. They are reflected as an opaque value, and serialized as the names
+    /// of the flags that are set, such as `"LeftClick | Shift"`.
+    pub struct InputFlags: u16 {
+        const LeftClick = 0b00000000001;
+        const RightClick = 0b00000000010;
+        const MiddleClick = 0b00000000100;
+        const Shift = 0b00000001000;
+        const Ctrl = 0b00000010000;
+        const Alt = 0b00000100000;
+        /// The mouse's back side button.
+        const Back = 0b00001000000;
+        /// The mouse's forward side button.
+        const Forward = 0b00010000000;
+        /// The Windows, Command or Super key.
+        const Super = 0b00100000000;
+        /// A further mouse button, bound to one of the `MouseButton::Other` buttons in the `DragInputMap`.
+        const Extra1 = 0b01000000000;
+        /// A further mouse button, bound to one of the `MouseButton::Other` buttons in the `DragInputMap`.
+        const Extra2 = 0b10000000000;
+        const Clicks = 0b11011000111;
+        const Modifiers = 0b00100111000;
+    }
+}
+
+impl InputFlags {
+    /// Returns `flag` if `cond` is true, and no flags otherwise.
+    pub fn from_bool(flag: InputFlags, cond: bool) -> InputFlags {
+        if cond {
+            flag
+        } else {
+            InputFlags::empty()
+        }
+    }
+
+    /// Returns the flag of a mouse button, or no flags for buttons that aren't tracked. `MouseButton::Other` buttons
+    /// are numbered differently on every mouse, so they only set `Extra1` or `Extra2` once bound in the `DragInputMap`.
+    pub fn from_mouse_button(button: MouseButton) -> InputFlags {
+        match button {
+            MouseButton::Left => InputFlags::LeftClick,
+            MouseButton::Right => InputFlags::RightClick,
+            MouseButton::Middle => InputFlags::MiddleClick,
+            MouseButton::Back => InputFlags::Back,
+            MouseButton::Forward => InputFlags::Forward,
+            MouseButton::Other(_) => InputFlags::empty(),
+        }
+    }
+
+    /// Returns the flag of a modifier key, either the left or right one, or no flags for other keys.
+    pub fn from_key(key: KeyCode) -> InputFlags {
+        match key {
+            KeyCode::ShiftLeft | KeyCode::ShiftRight => InputFlags::Shift,
+            KeyCode::ControlLeft | KeyCode::ControlRight => InputFlags::Ctrl,
+            KeyCode::AltLeft | KeyCode::AltRight => InputFlags::Alt,
+            KeyCode::SuperLeft | KeyCode::SuperRight => InputFlags::Super,
+            _ => InputFlags::empty(),
+        }
+    }
+
+    /// Sets `flag` if `cond` is true, leaving the flags as they are otherwise.
+    pub fn set_if(&mut self, flag: InputFlags, cond: bool) {
+        if cond {
+            self.insert(flag);
+        }
+    }
+
+    /// Returns true if either shift key is pressed.
+    pub fn is_shift(&self) -> bool {
+        self.contains(InputFlags::Shift)
+    }
+
+    /// Returns true if either control key is pressed.
+    pub fn is_ctrl(&self) -> bool {
+        self.contains(InputFlags::Ctrl)
+    }
+
+    /// Returns true if either alt key is pressed.
+    pub fn is_alt(&self) -> bool {
+        self.contains(InputFlags::Alt)
+    }
+
+    /// Returns true if any modifier key is pressed.
+    pub fn any_modifier(&self) -> bool {
+        self.intersects(InputFlags::Modifiers)
+    }
+
+    /// Returns the number of mouse buttons that are pressed.
+    pub fn click_count(&self) -> u32 {
+        self.clicks().bits().count_ones()
+    }
+
+    /// Returns true if the left mouse button is the only one pressed, whichever modifier keys are.
+    pub fn only_left_click(&self) -> bool {
+        self.clicks() == InputFlags::LeftClick
+    }
+
+    /// Returns true if all of `required` is pressed and no other mouse button is. Modifier keys that aren't required
+    /// may still be pressed.
+    pub fn matches_exactly(&self, required: InputFlags) -> bool {
+        self.contains(required) && self.clicks() == required.clicks()
+    }
+
+    /// Returns true if any mouse button is pressed.
+    pub fn any_click(&self) -> bool {
+        self.intersects(InputFlags::Clicks)
+    }
+
+    /// Returns true if the primary (left) mouse button is pressed.
+    pub fn primary_click(&self) -> bool {
+        self.contains(InputFlags::LeftClick)
+    }
+
+    /// Returns only the mouse buttons that are pressed.
+    pub fn clicks(&self) -> InputFlags {
+        *self & InputFlags::Clicks
+    }
+
+    /// Returns only the modifier keys that are pressed.
+    pub fn modifiers(&self) -> InputFlags {
+        *self & InputFlags::Modifiers
+    }
+
+    /// Returns the number of mouse buttons and modifier keys that are pressed.
+    pub fn pressed_count(&self) -> u32 {
+        (*self & (InputFlags::Clicks | InputFlags::Modifiers))
+            .bits()
+            .count_ones()
+    }
+}
+
+/// Treats `rhs` as a boolean, keeping the flags if it is non-zero. Prefer `InputFlags::from_bool` or
+/// `InputFlags::set_if`.
+impl Mul<u8> for InputFlags {
+    type Output = Self;
+
+    fn mul(self, rhs: u8) -> Self {
+        Self::from_bool(self, rhs != 0)
+    }
+}
+
+/// Resource mapping the keys and mouse buttons that are read to the `InputFlags` they set, so that modifiers and clicks
+/// can be rebound at runtime without changing any `Draggable`. For example, Cmd can act as Ctrl on macOS with
+/// `input_map.keys.insert(KeyCode::SuperLeft, InputFlags::Ctrl)`.
+///
+/// The default map sets every flag from the button or either of the keys it is named after, leaving `Extra1` and
+/// `Extra2` unbound. Bind them to your mouse's further buttons with, for example,
+/// `input_map.buttons.insert(MouseButton::Other(8), InputFlags::Extra1)`. Entities with `DragNudge` are nudged with
+/// the arrow keys, which can be rebound the same way in `nudge_keys`.
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct DragInputMap {
+    /// Flags set while each key is pressed.
+    pub keys: HashMap<KeyCode, InputFlags>,
+    /// Flags set while each mouse button is pressed.
+    pub buttons: HashMap<MouseButton, InputFlags>,
+    /// Direction each key nudges dragged entities with `DragNudge` in while it is pressed, with positive y pointing up.
+    pub nudge_keys: HashMap<KeyCode, Vec2>,
+}
+
+impl Default for DragInputMap {
+    fn default() -> Self {
+        let keys = [
+            KeyCode::ShiftLeft,
+            KeyCode::ShiftRight,
+            KeyCode::ControlLeft,
+            KeyCode::ControlRight,
+            KeyCode::AltLeft,
+            KeyCode::AltRight,
+            KeyCode::SuperLeft,
+            KeyCode::SuperRight,
+        ];
+        let buttons = [
+            MouseButton::Left,
+            MouseButton::Right,
+            MouseButton::Middle,
+            MouseButton::Back,
+            MouseButton::Forward,
+        ];
+        DragInputMap {
+            keys: keys
+                .into_iter()
+                .map(|key| (key, InputFlags::from_key(key)))
+                .collect(),
+            buttons: buttons
+                .into_iter()
+                .map(|button| (button, InputFlags::from_mouse_button(button)))
+                .collect(),
+            nudge_keys: [
+                (KeyCode::ArrowRight, Vec2::X),
+                (KeyCode::ArrowLeft, Vec2::NEG_X),
+                (KeyCode::ArrowUp, Vec2::Y),
+                (KeyCode::ArrowDown, Vec2::NEG_Y),
+            ]
+            .into_iter()
+            .collect(),
+        }
+    }
+}
+
+impl DragInputMap {
+    /// Flags set by the keys and mouse buttons that are pressed.
+    pub fn flags(
+        &self,
+        keys: &ButtonInput<KeyCode>,
+        buttons: &ButtonInput<MouseButton>,
+    ) -> InputFlags {
+        let clicks = buttons
+            .get_pressed()
+            .filter_map(|button| self.buttons.get(button));
+        let modifiers = keys.get_pressed().filter_map(|key| self.keys.get(key));
+        clicks.chain(modifiers).copied().collect()
+    }
+
+    /// Direction the keys that are pressed nudge dragged entities in, with a length of at most 1.
+    pub fn nudge(&self, keys: &ButtonInput<KeyCode>) -> Vec2 {
+        keys.get_pressed()
+            .filter_map(|key| self.nudge_keys.get(key))
+            .sum::<Vec2>()
+            .clamp_length_max(1.0)
+    }
+}
+
+/// Resource holding the inputs and cursor position that the plugin's systems drag with, so that they can come from
+/// somewhere other than the mouse and keyboard, such as an input mapping crate.
+///
+/// By default it is filled in `DragSystem::Input` from the mouse buttons and keys bound in the `DragInputMap`, the
+/// left stick of every gamepad, and the position of the cursor in whichever window it is over. To fill it yourself, add the plugin with
+/// `default_input` turned off, and set it from a system in `DragSystem::Input`.
+#[derive(Resource, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct DragInputState {
+    /// Inputs currently pressed.
+    pub inputs: InputFlags,
+    /// Window the cursor is over along with its logical position in it, or `None` while it is outside of every window.
+    pub cursor: Option<(Entity, Vec2)>,
+    /// Direction to move dragged entities with `DragNudge` in, with positive y pointing up and a length of at most 1.
+    pub nudge: Vec2,
+    /// Inputs that were pressed last frame.
+    pub(crate) previous: InputFlags,
+}
+
+impl DragInputState {
+    /// Inputs that are pressed now but weren't last frame.
+    pub fn just_pressed(&self) -> InputFlags {
+        self.inputs - self.previous
+    }
+}
+
+/// Fills `DragInputState` from the mouse and keyboard, and from the cursor in whichever window it is over, preferring
+/// the focused window.
+pub(crate) fn read_input(
+    mut input: ResMut<DragInputState>,
+    input_map: Res<DragInputMap>,
+    keys: Res<ButtonInput<KeyCode>>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    q_gamepads: Query<&Gamepad>,
+    q_windows: Query<(Entity, &Window)>,
+) {
+    input.inputs = input_map.flags(&keys, &buttons);
+    input.nudge = q_gamepads
+        .iter()
+        .map(Gamepad::left_stick)
+        .fold(input_map.nudge(&keys), |nudge, stick| nudge + stick)
+        .clamp_length_max(1.0);
+    input.cursor = q_windows
+        .iter()
+        .filter_map(|(entity, window)| Some((entity, window.cursor_position()?, window.focused)))
+        .max_by_key(|(.., focused)| *focused)
+        .map(|(entity, logical, _)| (entity, logical));
+}
+
+/// Remembers this frame's inputs, for `DragInputState::just_pressed` next frame.
+pub(crate) fn remember_input(mut input: ResMut<DragInputState>) {
+    input.previous = input.inputs;
+}
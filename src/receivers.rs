@@ -0,0 +1,730 @@
+//! Indexing receivers and finding the ones a dragged entity is over.
+
+use bevy::{
+    ecs::system::SystemParam,
+    platform::collections::HashMap,
+    prelude::*,
+    render::primitives::Aabb,
+    ui::{CalculatedClip, ComputedNodeTarget},
+};
+
+use crate::{
+    components::{
+        DragAccepts, DragGroup, DragHitbox, DragKind, Dragging, DropValidation, DropZone,
+        DwellTrigger, Hovered, HoveredFull, PixelPerfect, Receiver, ReceiverCapacity,
+        ReceiverDisabled, ReceiverMode, ReceiverPriority, ReorderAxis, ReorderList,
+    },
+    events::{HoverEnter, HoverExit, HoveredChange, ReorderHover},
+    hit_test::{
+        get_bounds, local_cursor, oriented_corners, ray_aabb_distance, Bounds, CursorPosition,
+        DrawOrder, HitTarget, HitTargetItem, PixelTest, SpriteSizes,
+    },
+    resources::ActiveDrag,
+};
+
+/// Resource that indexes the bounds of every receiver in a uniform grid, so that finding the receivers under
+/// the cursor doesn't require testing every receiver. Receivers that move or change bounds are updated in place, while
+/// the index is rebuilt whenever a receiver is removed or disabled, or an image loads.
+#[derive(Resource, Default)]
+pub(crate) struct ReceiverIndex {
+    /// Every receiver that can be hovered.
+    receivers: Vec<IndexedReceiver>,
+    /// Index of each receiver in `receivers`.
+    entries: HashMap<Entity, usize>,
+    /// UI receivers, grouped by the camera they are rendered by.
+    ui: HashMap<Entity, ReceiverGrid>,
+    world: ReceiverGrid,
+    /// Receivers tested against the cursor's ray, which can't be put in a 2D grid.
+    volumes: Vec<usize>,
+}
+
+/// What is needed to hit test a receiver in the `ReceiverIndex`.
+pub(crate) struct IndexedReceiver {
+    entity: Entity,
+    bounds: Bounds,
+    gtransform: GlobalTransform,
+    pixel_test: Option<PixelTest>,
+    /// Rectangle the receiver is clipped to by its UI ancestors, if any.
+    clip: Option<Rect>,
+    /// Converts the physical pixels UI receivers are laid out in to `Val::Px` units.
+    inverse_scale_factor: f32,
+    priority: i32,
+    /// Number of ancestors the receiver has.
+    depth: usize,
+    /// Stack index of UI receivers, see `DrawOrder`.
+    stack_index: Option<u32>,
+    placement: Placement,
+}
+
+/// Where a receiver is looked up from in the `ReceiverIndex`.
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Placement {
+    /// In the grid of UI receivers rendered by the camera, under the rectangle.
+    Ui(Entity, Rect),
+    /// In the grid of world receivers, under the rectangle.
+    World(Rect),
+    /// Among the volumes.
+    Volume,
+}
+
+impl IndexedReceiver {
+    /// Receiver ready to be indexed, if it can be hovered. Receivers without known bounds can't be, until their image
+    /// loads.
+    fn new(
+        target: &HitTargetItem,
+        priority: Option<&ReceiverPriority>,
+        depth: usize,
+        sprite_sizes: &SpriteSizes,
+    ) -> Option<Self> {
+        let bounds = get_bounds(target, sprite_sizes)?;
+        let placement = match bounds {
+            Bounds::Ui(_) | Bounds::UiCircle { .. } => {
+                // UI receivers can only be hovered through the cursor of the window their camera renders to
+                let camera = target.node_target.and_then(ComputedNodeTarget::camera)?;
+                Placement::Ui(camera, bounds.rect())
+            }
+            Bounds::World(rect) => Placement::World(rect),
+            Bounds::Oriented(_) | Bounds::Circle { .. } => {
+                // Indexed by the world rectangle around it, the exact test happens on lookup
+                let corners = oriented_corners(target.gtransform, bounds.rect());
+                let around = corners.iter().fold(
+                    Rect::from_corners(corners[0], corners[0]),
+                    |around, corner| around.union_point(*corner),
+                );
+                Placement::World(around)
+            }
+            Bounds::Volume(_) => Placement::Volume,
+        };
+        Some(IndexedReceiver {
+            entity: target.entity,
+            bounds,
+            gtransform: *target.gtransform,
+            pixel_test: PixelTest::new(target, sprite_sizes),
+            clip: target.clip.map(|clip| clip.clip),
+            inverse_scale_factor: target
+                .computed_node
+                .map_or(1.0, |computed_node| computed_node.inverse_scale_factor()),
+            priority: priority.map_or(0, |priority| priority.0),
+            depth,
+            stack_index: target.computed_node.map(ComputedNode::stack_index),
+            placement,
+        })
+    }
+}
+
+impl ReceiverIndex {
+    pub(crate) fn rebuild<'a>(
+        &mut self,
+        targets: impl Iterator<Item = (HitTargetItem<'a>, Option<&'a ReceiverPriority>, usize)>,
+        sprite_sizes: &SpriteSizes,
+    ) {
+        self.receivers = targets
+            .filter_map(|(target, priority, depth)| {
+                IndexedReceiver::new(&target, priority, depth, sprite_sizes)
+            })
+            .collect();
+        self.entries = self
+            .receivers
+            .iter()
+            .enumerate()
+            .map(|(index, receiver)| (receiver.entity, index))
+            .collect();
+        self.volumes.clear();
+        let mut ui: HashMap<Entity, Vec<(usize, Rect)>> = HashMap::default();
+        let mut world = Vec::new();
+        for (index, receiver) in self.receivers.iter().enumerate() {
+            match receiver.placement {
+                Placement::Ui(camera, rect) => ui.entry(camera).or_default().push((index, rect)),
+                Placement::World(rect) => world.push((index, rect)),
+                Placement::Volume => self.volumes.push(index),
+            }
+        }
+        self.ui = ui
+            .iter()
+            .map(|(camera, rects)| (*camera, ReceiverGrid::new(rects)))
+            .collect();
+        self.world = ReceiverGrid::new(&world);
+    }
+
+    /// Updates the receivers that changed in place, moving them to the cells they now cover. Returns false, leaving
+    /// the index to be rebuilt, if one of them can no longer be hovered.
+    fn update<'a>(
+        &mut self,
+        targets: impl Iterator<Item = (HitTargetItem<'a>, Option<&'a ReceiverPriority>, usize)>,
+        sprite_sizes: &SpriteSizes,
+    ) -> bool {
+        for (target, priority, depth) in targets {
+            let receiver = IndexedReceiver::new(&target, priority, depth, sprite_sizes);
+            match (self.entries.get(&target.entity).copied(), receiver) {
+                (Some(index), Some(receiver)) => {
+                    self.unplace(index);
+                    self.receivers[index] = receiver;
+                    self.place(index);
+                }
+                (Some(_), None) => return false,
+                (None, Some(receiver)) => {
+                    let index = self.receivers.len();
+                    self.entries.insert(receiver.entity, index);
+                    self.receivers.push(receiver);
+                    self.place(index);
+                }
+                (None, None) => {}
+            }
+        }
+        true
+    }
+
+    fn place(&mut self, index: usize) {
+        match self.receivers[index].placement {
+            Placement::Ui(camera, rect) => self.ui.entry(camera).or_default().insert(index, rect),
+            Placement::World(rect) => self.world.insert(index, rect),
+            Placement::Volume => self.volumes.push(index),
+        }
+    }
+
+    fn unplace(&mut self, index: usize) {
+        match self.receivers[index].placement {
+            Placement::Ui(camera, rect) => {
+                if let Some(grid) = self.ui.get_mut(&camera) {
+                    grid.remove(index, rect);
+                }
+            }
+            Placement::World(rect) => self.world.remove(index, rect),
+            Placement::Volume => self.volumes.retain(|volume| *volume != index),
+        }
+    }
+
+    /// Receivers under the cursor, in the order they take precedence.
+    pub(crate) fn receivers_at(
+        &self,
+        cursor: &CursorPosition,
+        images: &Assets<Image>,
+    ) -> Vec<Entity> {
+        let mut hits: Vec<(usize, DrawOrder)> = self
+            .ui
+            .get(&cursor.camera)
+            .into_iter()
+            .flat_map(|grid| grid.get(cursor.physical))
+            .chain(self.world.get(cursor.world()))
+            .chain(&self.volumes)
+            .copied()
+            .filter(|index| {
+                let receiver = &self.receivers[*index];
+                receiver
+                    .clip
+                    .is_none_or(|clip| clip.contains(cursor.physical))
+                    && receiver.bounds.contains(&receiver.gtransform, cursor)
+                    && receiver.pixel_test.is_none_or(|pixel_test| {
+                        pixel_test.contains(&receiver.gtransform, cursor, images)
+                    })
+            })
+            .map(|index| {
+                let receiver = &self.receivers[index];
+                let order = DrawOrder {
+                    ui: receiver.stack_index,
+                    distance: receiver.bounds.distance(&receiver.gtransform, cursor),
+                };
+                (index, order)
+            })
+            .collect();
+        // Among receivers tied on priority and depth, the one drawn on top comes first
+        hits.sort_unstable_by(|(a, a_order), (b, b_order)| {
+            let (a, b) = (&self.receivers[*a], &self.receivers[*b]);
+            b.priority
+                .cmp(&a.priority)
+                .then(b.depth.cmp(&a.depth))
+                .then(b_order.cmp(a_order))
+                .then(b.entity.cmp(&a.entity))
+        });
+        hits.into_iter()
+            .map(|(index, _)| self.receivers[index].entity)
+            .collect()
+    }
+
+    /// Where the cursor is within the receiver's bounds, see `Dropped::received_local`.
+    pub(crate) fn local_position(&self, receiver: Entity, cursor: &CursorPosition) -> Option<Vec2> {
+        let receiver = &self.receivers[*self.entries.get(&receiver)?];
+        let gtransform = &receiver.gtransform;
+        let min = receiver.bounds.rect().min;
+        match receiver.bounds {
+            // UI is laid out with y pointing down, so the top left corner is the minimum
+            Bounds::Ui(_) | Bounds::UiCircle { .. } => {
+                Some((cursor.physical - min) * receiver.inverse_scale_factor)
+            }
+            Bounds::World(_) => Some(cursor.world() - min),
+            Bounds::Oriented(_) | Bounds::Circle { .. } => {
+                Some(local_cursor(gtransform, cursor) - min)
+            }
+            Bounds::Volume(aabb) => {
+                let hit = cursor
+                    .ray
+                    .get_point(ray_aabb_distance(gtransform, &aabb, cursor.ray)?);
+                Some(
+                    gtransform
+                        .affine()
+                        .inverse()
+                        .transform_point3(hit)
+                        .truncate()
+                        - min,
+                )
+            }
+        }
+    }
+}
+
+/// Finds the receiver a dragged entity is over through the `ReceiverIndex`.
+#[derive(SystemParam)]
+pub(crate) struct ReceiverLookup<'w, 's> {
+    pub(crate) index: Res<'w, ReceiverIndex>,
+    images: Res<'w, Assets<Image>>,
+    q_kinds: Query<
+        'w,
+        's,
+        (
+            Option<&'static DragKind>,
+            Option<&'static DragGroup>,
+            Option<&'static DragAccepts>,
+        ),
+    >,
+    q_capacities: Query<'w, 's, (&'static ReceiverCapacity, Option<&'static Children>)>,
+    q_parents: Query<'w, 's, &'static ChildOf>,
+    q_dragged: Query<'w, 's, (), With<Dragging>>,
+    q_lists: Query<'w, 's, (&'static ReorderList, &'static Children)>,
+    q_items: Query<'w, 's, (&'static GlobalTransform, Has<ComputedNode>)>,
+    q_dwell: Query<'w, 's, &'static DwellTrigger>,
+}
+
+impl ReceiverLookup<'_, '_> {
+    /// Receiver under the cursor that takes precedence among those that accept the dragged entity, if any.
+    pub(crate) fn receiver_at(&self, cursor: &CursorPosition, dragged: Entity) -> Option<Entity> {
+        self.lookup(cursor, dragged).0
+    }
+
+    /// Receiver under the cursor that takes precedence among those that accept the dragged entity, along with
+    /// the first full receiver above it that would otherwise accept it.
+    pub(crate) fn lookup(
+        &self,
+        cursor: &CursorPosition,
+        dragged: Entity,
+    ) -> (Option<Entity>, Option<Entity>) {
+        let mut full = None;
+        for receiver in self.index.receivers_at(cursor, &self.images) {
+            if self.is_dragged(receiver, dragged) || !kinds_match(&self.q_kinds, dragged, receiver)
+            {
+                continue;
+            }
+            if !self.is_full(receiver, dragged) {
+                return (Some(receiver), full);
+            }
+            full = full.or(Some(receiver));
+        }
+        (None, full)
+    }
+
+    /// Whether the receiver is being dragged itself, or is carried along by a dragged ancestor, such as an item of a
+    /// reorderable list that is both draggable and a receiver. Those can't receive anything until they are dropped.
+    fn is_dragged(&self, receiver: Entity, dragged: Entity) -> bool {
+        std::iter::once(receiver)
+            .chain(self.q_parents.iter_ancestors(receiver))
+            .any(|entity| entity == dragged || self.q_dragged.contains(entity))
+    }
+
+    /// `ReorderList` that is the receiver or its parent, with the index among its children that the dragged entity
+    /// would be inserted at, counting the children before the cursor but not the entities being dragged.
+    pub(crate) fn insertion_index(
+        &self,
+        receiver: Entity,
+        cursor: &CursorPosition,
+        dragged: Entity,
+    ) -> Option<(Entity, usize)> {
+        let list_entity = std::iter::once(receiver)
+            .chain(self.q_parents.get(receiver).map(ChildOf::parent))
+            .find(|entity| self.q_lists.contains(*entity))?;
+        let (list, children) = self.q_lists.get(list_entity).ok()?;
+        let index = children
+            .iter()
+            .filter(|child| *child != dragged && !self.q_dragged.contains(*child))
+            .filter_map(|child| self.q_items.get(child).ok())
+            .filter(|(gtransform, is_ui_node)| {
+                let center = gtransform.translation().truncate();
+                // UI is laid out with y pointing down, while it points up in the world
+                match (list.axis, is_ui_node) {
+                    (ReorderAxis::Vertical, true) => center.y < cursor.physical.y,
+                    (ReorderAxis::Vertical, false) => center.y > cursor.world().y,
+                    (ReorderAxis::Horizontal, true) => center.x < cursor.physical.x,
+                    (ReorderAxis::Horizontal, false) => center.x < cursor.world().x,
+                }
+            })
+            .count();
+        Some((list_entity, index))
+    }
+
+    /// Time hovered after which `HoverDwell` is next sent for the receiver, once it has been sent `dwells` times already.
+    pub(crate) fn next_dwell(&self, receiver: Entity, dwells: u32) -> Option<f64> {
+        self.q_dwell
+            .get(receiver)
+            .ok()
+            .and_then(|trigger| trigger.next_dwell(dwells))
+    }
+
+    fn is_full(&self, receiver: Entity, dragged: Entity) -> bool {
+        self.q_capacities
+            .get(receiver)
+            .is_ok_and(|(capacity, children)| {
+                let held = children.map_or(0, |children| {
+                    children.iter().filter(|child| *child != dragged).count()
+                });
+                held >= capacity.0
+            })
+    }
+}
+
+/// Writes `HoveredChange` along with the `HoverExit` and `HoverEnter` it implies, and `ReorderHover`.
+#[derive(SystemParam)]
+pub(crate) struct HoverWriter<'w> {
+    ew_hover: EventWriter<'w, HoveredChange>,
+    ew_exit: EventWriter<'w, HoverExit>,
+    ew_enter: EventWriter<'w, HoverEnter>,
+    pub(crate) ew_reorder: EventWriter<'w, ReorderHover>,
+}
+
+impl HoverWriter<'_> {
+    pub(crate) fn write(&mut self, event: HoveredChange) {
+        if let Some(receiver) = event.prevreceiver {
+            self.ew_exit.write(HoverExit {
+                dragged: event.hovered,
+                receiver,
+                inputs: event.inputs,
+            });
+        }
+        if let Some(receiver) = event.receiver {
+            self.ew_enter.write(HoverEnter {
+                dragged: event.hovered,
+                receiver,
+                inputs: event.inputs,
+            });
+        }
+        self.ew_hover.write(event);
+    }
+}
+
+/// Uniform grid mapping cells to the indices of the rectangles overlapping them.
+#[derive(Default)]
+pub(crate) struct ReceiverGrid {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<usize>>,
+    /// Rectangles covering too many cells to be worth inserting, which are always tested.
+    oversized: Vec<usize>,
+}
+
+impl ReceiverGrid {
+    /// Most cells a single rectangle may be inserted into.
+    const MAX_CELLS: i64 = 64;
+
+    fn new(rects: &[(usize, Rect)]) -> Self {
+        // Size cells after the average receiver so each one only covers a few cells
+        let average_size = rects
+            .iter()
+            .map(|(_, rect)| rect.size().max_element())
+            .sum::<f32>()
+            / rects.len().max(1) as f32;
+        let mut grid = ReceiverGrid {
+            cell_size: average_size.max(f32::EPSILON),
+            ..default()
+        };
+        for (index, rect) in rects {
+            grid.insert(*index, *rect);
+        }
+        grid
+    }
+
+    fn insert(&mut self, index: usize, rect: Rect) {
+        // Grids sized for nothing, such as the one built before any receiver was indexed, are sized after the first
+        // rectangle instead
+        if self.cell_size <= f32::EPSILON {
+            self.cell_size = rect.size().max_element().max(f32::EPSILON);
+        }
+        match self.cells_covered(rect) {
+            Some((min, max)) => {
+                for x in min.x..=max.x {
+                    for y in min.y..=max.y {
+                        self.cells.entry(IVec2::new(x, y)).or_default().push(index);
+                    }
+                }
+            }
+            None => self.oversized.push(index),
+        }
+    }
+
+    /// Removes a rectangle inserted with the same index and rectangle.
+    fn remove(&mut self, index: usize, rect: Rect) {
+        match self.cells_covered(rect) {
+            Some((min, max)) => {
+                for x in min.x..=max.x {
+                    for y in min.y..=max.y {
+                        let cell = IVec2::new(x, y);
+                        if let Some(indices) = self.cells.get_mut(&cell) {
+                            indices.retain(|other| *other != index);
+                            if indices.is_empty() {
+                                self.cells.remove(&cell);
+                            }
+                        }
+                    }
+                }
+            }
+            None => self.oversized.retain(|other| *other != index),
+        }
+    }
+
+    /// First and last cell the rectangle covers, or none if it covers too many.
+    fn cells_covered(&self, rect: Rect) -> Option<(IVec2, IVec2)> {
+        let min = self.cell(rect.min);
+        let max = self.cell(rect.max);
+        // Cells of huge rectangles may be far enough apart to overflow
+        let span = max.as_i64vec2() - min.as_i64vec2() + 1;
+        let covered = span.x.saturating_mul(span.y);
+        (1..=Self::MAX_CELLS)
+            .contains(&covered)
+            .then_some((min, max))
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    /// Indices of the rectangles that may contain the position.
+    fn get(&self, position: Vec2) -> impl Iterator<Item = &usize> {
+        self.cells
+            .get(&self.cell(position))
+            .into_iter()
+            .flatten()
+            .chain(&self.oversized)
+    }
+}
+
+/// Whether the receiver accepts entities of the dragged entity's kind and groups, see `DragKind` and `DragAccepts`.
+pub(crate) fn kinds_match(
+    q_kinds: &Query<(Option<&DragKind>, Option<&DragGroup>, Option<&DragAccepts>)>,
+    dragged: Entity,
+    receiver: Entity,
+) -> bool {
+    let Ok((receiver_kind, _, accepts)) = q_kinds.get(receiver) else {
+        return true;
+    };
+    let (dragged_kind, group) = q_kinds
+        .get(dragged)
+        .map_or((None, None), |(kind, group, _)| (kind, group));
+    receiver_kind.is_none_or(|kind| dragged_kind == Some(kind))
+        && accepts.is_none_or(|accepts| group.is_some_and(|group| group.0 & accepts.0 != 0))
+}
+
+/// Parents the dropped entity to the receiver according to its `ReceiverMode`, returning the entity it displaced.
+pub(crate) fn apply_receiver_mode(
+    commands: &mut Commands,
+    q_entities: &Query<EntityRef>,
+    dropped: Entity,
+    dragging: &Dragging,
+    received: Option<Entity>,
+) -> Option<Entity> {
+    let receiver = q_entities.get(received?).ok()?;
+    let mode = *receiver.get::<ReceiverMode>()?;
+    let held: Vec<Entity> = receiver
+        .get::<Children>()
+        .map(|children| children.iter().filter(|child| *child != dropped).collect())
+        .unwrap_or_default();
+    let mut displaced = None;
+    match mode {
+        ReceiverMode::Swap => {
+            // Nodes that stayed in their container while dragged weren't reparented, so they are still in it
+            let origin = dragging.original_parent.or_else(|| {
+                q_entities
+                    .get(dropped)
+                    .ok()
+                    .and_then(|entity| entity.get::<ChildOf>())
+                    .map(ChildOf::parent)
+            });
+            if let Some(origin) = origin.filter(|origin| *origin != receiver.id()) {
+                commands.entity(origin).add_children(&held);
+                displaced = held.first().copied();
+            }
+        }
+        ReceiverMode::Replace => {
+            for child in held {
+                commands.entity(child).despawn();
+            }
+        }
+        ReceiverMode::Stack => {}
+    }
+    commands.entity(receiver.id()).add_child(dropped);
+    // UI nodes were positioned absolutely while they followed the cursor, which would keep them out of the
+    // receiver's layout
+    if let Some((_, Some(origin))) = dragging.origin.clone() {
+        commands
+            .entity(dropped)
+            .entry::<Node>()
+            .and_modify(move |mut node| {
+                node.position_type = origin.position_type;
+                node.left = origin.left;
+                node.top = origin.top;
+                node.right = origin.right;
+                node.bottom = origin.bottom;
+                node.margin = origin.margin;
+                node.display = origin.display;
+            });
+    }
+    displaced
+}
+
+/// The receiver if it accepts the dropped entity, see `DropValidation`.
+pub(crate) fn validate_drop(
+    q_entities: &Query<EntityRef>,
+    dropped: Entity,
+    received: Option<Entity>,
+) -> Option<Entity> {
+    received.filter(|receiver| {
+        let (Ok(dropped), Ok(receiver)) = (q_entities.get(dropped), q_entities.get(*receiver))
+        else {
+            return false;
+        };
+        receiver
+            .get::<DropValidation>()
+            .is_none_or(|validation| (validation.0)(dropped, receiver))
+    })
+}
+
+/// Reads what a drop on a receiver needs, for `validate_drop` and `apply_receiver_mode`.
+#[derive(SystemParam)]
+pub(crate) struct DropTargets<'w, 's> {
+    pub(crate) q_entities: Query<'w, 's, EntityRef<'static>>,
+    pub(crate) index: Res<'w, ReceiverIndex>,
+}
+
+/// Keeps `Hovered` on the `DropZone` receivers that dragged entities are hovering over.
+pub(crate) fn update_hovered(
+    mut commands: Commands,
+    active_drag: Res<ActiveDrag>,
+    q_zones: Query<Has<Hovered>, With<DropZone>>,
+    q_hovered: Query<Entity, (With<DropZone>, With<Hovered>)>,
+) {
+    if !active_drag.is_changed() {
+        return;
+    }
+    let hovered: Vec<Entity> = active_drag
+        .iter()
+        .filter_map(|(_, hovering)| hovering)
+        .collect();
+    for entity in q_hovered.iter() {
+        if !hovered.contains(&entity) {
+            commands.entity(entity).remove::<Hovered>();
+        }
+    }
+    for receiver in hovered {
+        if let Ok(false) = q_zones.get(receiver) {
+            commands.entity(receiver).insert(Hovered);
+        }
+    }
+}
+
+/// Keeps `HoveredFull` on the full receivers that dragged entities are over.
+pub(crate) fn update_hovered_full(
+    mut commands: Commands,
+    q_dragging: Query<&Dragging>,
+    q_full: Query<Entity, With<HoveredFull>>,
+) {
+    let hovered: Vec<Entity> = q_dragging
+        .iter()
+        .filter_map(|dragging| dragging.hovering_full)
+        .collect();
+    for entity in q_full.iter() {
+        if !hovered.contains(&entity) {
+            commands.entity(entity).remove::<HoveredFull>();
+        }
+    }
+    for receiver in hovered {
+        if !q_full.contains(receiver) {
+            commands.entity(receiver).try_insert(HoveredFull);
+        }
+    }
+}
+
+/// Receivers that can be hovered.
+type EnabledReceiver = (With<Receiver>, Without<ReceiverDisabled>);
+
+/// Receivers whose bounds, or whether they can be hovered, may have changed.
+type ChangedReceiver = (
+    With<Receiver>,
+    Or<(
+        Added<Receiver>,
+        Changed<GlobalTransform>,
+        Changed<ComputedNode>,
+        Changed<ComputedNodeTarget>,
+        Changed<Sprite>,
+        Changed<Aabb>,
+        Changed<DragHitbox>,
+        Changed<PixelPerfect>,
+        Changed<CalculatedClip>,
+        Added<ReceiverDisabled>,
+        Changed<ReceiverPriority>,
+        Changed<ChildOf>,
+    )>,
+);
+
+/// Reads the removals of the components that make an entity a receiver or decide its bounds.
+#[derive(SystemParam)]
+pub(crate) struct RemovedReceiverParts<'w, 's> {
+    receivers: RemovedComponents<'w, 's, Receiver>,
+    hitboxes: RemovedComponents<'w, 's, DragHitbox>,
+    pixel_perfect: RemovedComponents<'w, 's, PixelPerfect>,
+    clips: RemovedComponents<'w, 's, CalculatedClip>,
+    disabled: RemovedComponents<'w, 's, ReceiverDisabled>,
+    priorities: RemovedComponents<'w, 's, ReceiverPriority>,
+}
+
+impl RemovedReceiverParts<'_, '_> {
+    /// Whether any were removed since the system last ran. Every removal is read, so that stale ones don't count later.
+    fn any(&mut self) -> bool {
+        let counts = [
+            self.receivers.read().count(),
+            self.hitboxes.read().count(),
+            self.pixel_perfect.read().count(),
+            self.clips.read().count(),
+            self.disabled.read().count(),
+            self.priorities.read().count(),
+        ];
+        counts.iter().any(|count| *count > 0)
+    }
+}
+
+pub(crate) fn update_receiver_index(
+    mut receiver_index: ResMut<ReceiverIndex>,
+    q_receivers: Query<(HitTarget, Option<&ReceiverPriority>), EnabledReceiver>,
+    q_changed: Query<Entity, ChangedReceiver>,
+    mut removed: RemovedReceiverParts,
+    q_parents: Query<&ChildOf>,
+    mut er_images: EventReader<AssetEvent<Image>>,
+    sprite_sizes: SpriteSizes,
+) {
+    // Image events are consumed regardless so that stale ones don't trigger a rebuild later
+    let images_changed = er_images.read().count() > 0;
+    let removed = removed.any();
+    let depth = |entity| q_parents.iter_ancestors(entity).count();
+    if !removed && !images_changed {
+        // Receivers that were just disabled have to be removed, which takes a rebuild
+        let changed: Option<Vec<_>> = q_changed
+            .iter()
+            .map(|entity| {
+                let (target, priority) = q_receivers.get(entity).ok()?;
+                Some((target, priority, depth(entity)))
+            })
+            .collect();
+        if changed.is_some_and(|changed| receiver_index.update(changed.into_iter(), &sprite_sizes))
+        {
+            return;
+        }
+    }
+    let targets = q_receivers.iter().map(|(target, priority)| {
+        let depth = depth(target.entity);
+        (target, priority, depth)
+    });
+    receiver_index.rebuild(targets, &sprite_sizes);
+}
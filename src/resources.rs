@@ -0,0 +1,107 @@
+//! Resources tracking active drags and holding plugin-wide settings.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::components::{DragZIndex, GrabMode};
+
+/// Resource listing every entity that is currently being dragged along with the receiver it is hovering, if any.
+/// It is kept up to date by the plugin's systems, so it can be read from anywhere without querying for `Dragging`.
+#[derive(Resource, Default, Debug)]
+pub struct ActiveDrag {
+    dragging: HashMap<Entity, Option<Entity>>,
+}
+
+impl ActiveDrag {
+    /// Returns true if anything is being dragged.
+    pub fn is_active(&self) -> bool {
+        !self.dragging.is_empty()
+    }
+
+    /// Returns true if the entity is being dragged.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.dragging.contains_key(&entity)
+    }
+
+    /// Receiver that the dragged entity is hovering, if the entity is being dragged and hovering one.
+    pub fn hovering(&self, entity: Entity) -> Option<Entity> {
+        self.dragging.get(&entity).copied().flatten()
+    }
+
+    /// Iterates over every dragged entity along with the receiver it is hovering, if any.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Option<Entity>)> + '_ {
+        self.dragging
+            .iter()
+            .map(|(dragged, hovering)| (*dragged, *hovering))
+    }
+
+    /// Number of entities being dragged.
+    pub fn len(&self) -> usize {
+        self.dragging.len()
+    }
+
+    /// Returns true if nothing is being dragged.
+    pub fn is_empty(&self) -> bool {
+        self.dragging.is_empty()
+    }
+
+    pub(crate) fn start(&mut self, entity: Entity) {
+        self.dragging.insert(entity, None);
+    }
+
+    pub(crate) fn set_hovering(&mut self, entity: Entity, hovering: Option<Entity>) {
+        if let Some(entry) = self.dragging.get_mut(&entity) {
+            *entry = hovering;
+        }
+    }
+
+    pub(crate) fn end(&mut self, entity: Entity) {
+        self.dragging.remove(&entity);
+    }
+}
+
+/// Resource holding plugin-wide defaults, used for entities that don't configure these themselves.
+/// `DragPlugin` inserts the default settings, unless the resource was inserted before the plugin was added.
+#[derive(Resource, Clone, Copy, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct DragSettings {
+    /// Z-index given to dragged UI entities that don't have a `DragZIndex`.
+    pub dragged_z_index: DragZIndex,
+    /// Which point of an entity is held under the cursor when its drag is started by a `DragCommand`, rather than by
+    /// grabbing it. Like `Draggable::grab_offset`, it defaults to `GrabMode::Cursor`, which leaves the entity where it
+    /// is.
+    pub default_grab_mode: GrabMode,
+    /// Minimum time in seconds for buttons to be held before dragging initiates, for draggables that don't set
+    /// `Draggable::minimum_held`.
+    pub default_minimum_held: Option<f64>,
+    /// If true, dragged UI entities are taken out of their container and positioned absolutely at the root, otherwise
+    /// they are offset from their place in the container. Overridden by `DragReparent`.
+    pub reparent_ui: bool,
+    /// Clock that `Draggable::minimum_held` is measured with.
+    pub hold_clock: HoldClock,
+    /// If true, the default, waiting out `Draggable::minimum_held` is cancelled when the cursor leaves the entity.
+    /// Otherwise the entity is dragged once the wait ends wherever the cursor went.
+    pub hold_in_bounds: bool,
+}
+
+/// Clock that buttons are held against before dragging initiates, see `Draggable::minimum_held`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum HoldClock {
+    /// `Time<Real>`, which keeps running while the game is paused.
+    #[default]
+    Real,
+    /// `Time<Virtual>`, which stops while the game is paused, so holds only charge while it runs.
+    Virtual,
+}
+
+impl Default for DragSettings {
+    fn default() -> Self {
+        DragSettings {
+            dragged_z_index: DragZIndex::default(),
+            default_grab_mode: GrabMode::Cursor,
+            default_minimum_held: None,
+            reparent_ui: true,
+            hold_clock: HoldClock::Real,
+            hold_in_bounds: true,
+        }
+    }
+}
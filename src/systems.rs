@@ -0,0 +1,1286 @@
+//! Systems that start, move and end drags.
+
+use bevy::{
+    ecs::{query::QueryData, system::SystemParam},
+    input::mouse::MouseWheel,
+    prelude::*,
+    window::{CursorLeft, WindowFocused},
+};
+use std::time::Duration;
+
+#[cfg(feature = "debug")]
+use crate::events::{DragStartFailed, DragStartFailure};
+use crate::{
+    components::{
+        AwaitingDrag, DragHandle, DragInertia, DragNudge, DragOffset, DragPlane, DragReparent,
+        DragSmoothing, DragZIndex, Draggable, Dragging, DropOutside, GrabMode, LostCursor,
+        NudgeMode, OutsideRelease, SavedZIndex, Selected,
+    },
+    events::{
+        DragAwait, DragAwaitCancelled, DragCancelled, DragCommand, DragInputChanged, DragMove,
+        DragMoved, DragNudged, DragScroll, Dragged, Dropped, HoverDwell, HoverEnter, HoverExit,
+        HoveredChange, ReorderHover,
+    },
+    hit_test::{
+        get_drag_offset, get_grab_offset, is_in_bounds, select_candidate, world_drag_offset,
+        CursorPosition, DragCameraQuery, DragHitTest, DrawOrder, HitTarget, SpriteSizes,
+    },
+    input::{DragInputState, InputFlags},
+    receivers::{apply_receiver_mode, validate_drop, DropTargets, HoverWriter, ReceiverLookup},
+    resources::{ActiveDrag, DragSettings, HoldClock},
+};
+
+/// Reads the time on the clock chosen by `DragSettings::hold_clock`.
+#[derive(SystemParam)]
+pub(crate) struct HoldTime<'w> {
+    settings: Res<'w, DragSettings>,
+    real: Res<'w, Time<Real>>,
+    virtual_time: Res<'w, Time<Virtual>>,
+}
+
+impl HoldTime<'_> {
+    fn elapsed(&self) -> f64 {
+        match self.settings.hold_clock {
+            HoldClock::Real => self.real.elapsed_secs_f64(),
+            HoldClock::Virtual => self.virtual_time.elapsed_secs_f64(),
+        }
+    }
+}
+
+/// Reads `Time<Real>` for systems that move entities over time. Its delta is measured from the previous time the
+/// system ran, which stays correct in schedules that don't run exactly once a frame, such as `FixedUpdate`.
+#[derive(SystemParam)]
+pub(crate) struct DragTime<'w, 's> {
+    real: Res<'w, Time<Real>>,
+    last_run: Local<'s, Option<Duration>>,
+}
+
+impl DragTime<'_, '_> {
+    fn elapsed_secs_f64(&self) -> f64 {
+        self.real.elapsed_secs_f64()
+    }
+
+    /// Seconds since the system last ran. Only call it once per run.
+    fn delta_secs(&mut self) -> f32 {
+        let now = self.real.elapsed();
+        match self.last_run.replace(now) {
+            Some(last_run) => (now - last_run).as_secs_f32(),
+            None => self.real.delta_secs(),
+        }
+    }
+}
+
+/// Writes the events of a drag, from the wait before it starts to its drop, and keeps `ActiveDrag` up to date with
+/// the drags it starts and ends.
+#[derive(SystemParam)]
+pub(crate) struct DragWriter<'w> {
+    active_drag: ResMut<'w, ActiveDrag>,
+    ew_await: EventWriter<'w, DragAwait>,
+    ew_await_cancelled: EventWriter<'w, DragAwaitCancelled>,
+    ew_dragged: EventWriter<'w, Dragged>,
+    ew_input_changed: EventWriter<'w, DragInputChanged>,
+    ew_move: EventWriter<'w, DragMove>,
+    ew_moved: EventWriter<'w, DragMoved>,
+    ew_hover: HoverWriter<'w>,
+    ew_dwell: EventWriter<'w, HoverDwell>,
+    ew_dropped: EventWriter<'w, Dropped>,
+    ew_cancelled: EventWriter<'w, DragCancelled>,
+}
+
+impl DragWriter<'_> {
+    /// Starts dragging the grabbed entity, along with the selected entities grabbed with it.
+    fn start(
+        &mut self,
+        commands: &mut Commands,
+        (grabbed, grab_offset): (Entity, Vec3),
+        group: &[(Entity, Vec3)],
+        camera: Entity,
+        (screen_position, world_position): (Vec2, Vec2),
+        inputs: InputFlags,
+    ) {
+        for (entity, offset) in std::iter::once((grabbed, grab_offset)).chain(group.iter().copied())
+        {
+            // Selected entities may have been despawned while the drag was awaited
+            let Ok(mut entity_commands) = commands.get_entity(entity) else {
+                continue;
+            };
+            let mut dragging = Dragging::new(offset, Some(camera), inputs);
+            dragging.grab_screen_position = Some(screen_position);
+            dragging.group_offset = grab_offset - offset;
+            entity_commands
+                .insert(dragging)
+                .remove::<(AwaitingDrag, DragInertia)>();
+            let event = Dragged {
+                dragged: entity,
+                screen_position: Some(screen_position),
+                world_position: Some(world_position),
+                grab_offset: offset,
+                inputs,
+            };
+            self.ew_dragged.write(event);
+            commands.trigger_targets(event, entity);
+            self.active_drag.start(entity);
+        }
+    }
+
+    /// Cancels a drag, which leaves the receiver it hovers without being dropped.
+    fn cancel(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        draggable: &Draggable,
+        dragging: &Dragging,
+        cursor: Option<&CursorPosition>,
+        inputs: InputFlags,
+    ) {
+        self.ew_hover.write(HoveredChange::left(
+            entity,
+            dragging.hovering,
+            cursor,
+            inputs,
+        ));
+        self.ew_cancelled.write(DragCancelled {
+            dragged: entity,
+            original_parent: dragging.original_parent,
+            inputs,
+        });
+        self.end(commands, entity, draggable, dragging, None, false);
+    }
+
+    /// Cleans up after a drag ends, restoring what dragging changed. Entities that weren't received go back to their
+    /// original parent if `Draggable::restore_parent` is set, or if they are `returned` with `DropOutside::Return`.
+    fn end(
+        &mut self,
+        commands: &mut Commands,
+        entity: Entity,
+        draggable: &Draggable,
+        dragging: &Dragging,
+        received: Option<Entity>,
+        returned: bool,
+    ) {
+        commands.entity(entity).remove::<Dragging>();
+        self.active_drag.end(entity);
+        restore_z_index(commands, entity, dragging);
+        if let (true, None, Some(parent)) = (
+            draggable.restore_parent || returned,
+            received,
+            dragging.original_parent,
+        ) {
+            commands.entity(parent).add_child(entity);
+        }
+    }
+}
+
+/// Entities that are being dragged, or waiting for their drag to start.
+type DragBusy = Or<(With<Dragging>, With<AwaitingDrag>)>;
+
+/// Finds the draggable entities a click grabs, through their handles if they have any, and the selected entities
+/// dragged along with them.
+#[derive(SystemParam)]
+pub(crate) struct GrabQuery<'w, 's> {
+    q_draggable: Query<'w, 's, (HitTarget, &'static Draggable, Option<&'static DragPlane>)>,
+    q_handles: Query<'w, 's, HitTarget, With<DragHandle>>,
+    q_parents: Query<'w, 's, &'static ChildOf>,
+    q_selected: Query<'w, 's, (), With<Selected>>,
+    q_busy: Query<'w, 's, (), DragBusy>,
+    drag_camera: DragCameraQuery<'w, 's>,
+    sprite_sizes: SpriteSizes<'w>,
+}
+
+/// A draggable that a click could grab, with the offsets it would be held at.
+type GrabCandidate<'a> = (&'a Draggable, Vec3, Option<DragOffset>);
+
+pub(crate) fn startdrag(
+    mut commands: Commands,
+    grab: GrabQuery,
+    mut writer: DragWriter,
+    hold_time: HoldTime,
+    #[cfg(feature = "debug")] mut ew_failed: EventWriter<DragStartFailed>,
+) {
+    let GrabQuery {
+        q_draggable,
+        q_handles,
+        q_parents,
+        q_selected,
+        q_busy,
+        drag_camera,
+        sprite_sizes,
+    } = grab;
+    let input = &drag_camera.input;
+    let inputs = input.inputs;
+    let busy = !q_busy.is_empty() || writer.active_drag.is_active();
+    // Clicks made while another drag is active are still hit tested to report why they failed
+    #[cfg(feature = "debug")]
+    let report = input.just_pressed().any_click();
+    #[cfg(not(feature = "debug"))]
+    let report = false;
+
+    let mut candidates: Vec<(Entity, DrawOrder, GrabCandidate)> = Vec::new();
+    let mut selected: Vec<(Entity, Vec3)> = Vec::new();
+    let mut grab_cursor = None;
+
+    // Only a new click starts a drag, so one that was cancelled, or whose hold was, doesn't start again while the
+    // buttons are still held
+    if input.just_pressed().any_click() && (!busy || report) {
+        // Nothing can be hit tested until the cursor is over a window with a camera, such as while loading
+        if let Some((view, cursor)) = drag_camera.cursor() {
+            grab_cursor = Some(cursor);
+            // Map every handle to the draggable it belongs to, and whether the cursor is over it
+            let handles: Vec<(Entity, bool)> = q_handles
+                .iter()
+                .filter_map(|handle| {
+                    let owner = q_parents
+                        .iter_ancestors(handle.entity)
+                        .find(|ancestor| q_draggable.contains(*ancestor))?;
+                    Some((owner, is_in_bounds(&handle, &sprite_sizes, &cursor)))
+                })
+                .collect();
+
+            for (target, draggable, drag_plane) in q_draggable.iter() {
+                if !draggable.enabled && !report {
+                    continue;
+                }
+                if draggable.enabled && q_selected.contains(target.entity) {
+                    let grab_offset =
+                        get_grab_offset(GrabMode::Cursor, &target, drag_plane, &cursor);
+                    selected.push((target.entity, grab_offset));
+                }
+                let mut owned_handles = handles
+                    .iter()
+                    .filter(|(owner, _)| *owner == target.entity)
+                    .peekable();
+                let grabbed = if owned_handles.peek().is_some() {
+                    owned_handles.any(|(_, hit)| *hit)
+                } else {
+                    is_in_bounds(&target, &sprite_sizes, &cursor)
+                };
+                #[cfg(feature = "debug")]
+                if grabbed && report {
+                    let reason = if !draggable.enabled {
+                        Some(DragStartFailure::Disabled)
+                    } else if busy {
+                        Some(DragStartFailure::DragActive)
+                    } else if !inputs.contains(draggable.required) {
+                        Some(DragStartFailure::RequiredMissing(
+                            draggable.required.difference(inputs),
+                        ))
+                    } else if inputs.intersects(draggable.disallowed) {
+                        Some(DragStartFailure::DisallowedHeld(
+                            inputs.intersection(draggable.disallowed),
+                        ))
+                    } else {
+                        None
+                    };
+                    if let Some(reason) = reason {
+                        ew_failed.write(DragStartFailed {
+                            draggable: target.entity,
+                            reason,
+                            inputs,
+                        });
+                    }
+                }
+                if grabbed
+                    && !busy
+                    && draggable.enabled
+                    && inputs.contains(draggable.required)
+                    && !(inputs.intersects(draggable.disallowed))
+                {
+                    let grab_offset =
+                        get_grab_offset(draggable.grab_offset, &target, drag_plane, &cursor);
+                    let drag_offset = draggable
+                        .insert_offset
+                        .then(|| get_drag_offset(&target, &view, &cursor))
+                        .flatten();
+                    candidates.push((
+                        target.entity,
+                        DrawOrder::new(&target, &sprite_sizes, &cursor),
+                        (draggable, grab_offset, drag_offset),
+                    ));
+                }
+            }
+        }
+        let selection = select_candidate(candidates, |entity| {
+            q_parents.iter_ancestors(entity).collect()
+        });
+        if let (Some((grabbed, (draggable, grab_offset, drag_offset))), Some(cursor)) =
+            (selection, grab_cursor)
+        {
+            if let Some(drag_offset) = drag_offset {
+                commands.entity(grabbed).insert(drag_offset);
+            }
+            // Selected entities follow the grabbed one, keeping their offset from it
+            let group: Vec<(Entity, Vec3)> =
+                match selected.iter().find(|(entity, _)| *entity == grabbed) {
+                    Some((_, grabbed_offset)) => selected
+                        .iter()
+                        .filter(|(entity, _)| *entity != grabbed)
+                        .map(|(entity, offset)| (*entity, *offset - *grabbed_offset + grab_offset))
+                        .collect(),
+                    None => Vec::new(),
+                };
+            if let Some(x) = draggable
+                .minimum_held
+                .or(hold_time.settings.default_minimum_held)
+            {
+                let event = DragAwait {
+                    awaiting: grabbed,
+                    screen_position: cursor.logical,
+                    world_position: cursor.world(),
+                    grab_offset,
+                    inputs,
+                };
+                writer.ew_await.write(event);
+                commands.trigger_targets(event, grabbed);
+                let now = hold_time.elapsed();
+                commands.entity(grabbed).insert(AwaitingDrag {
+                    started: now,
+                    ends: now + x,
+                    progress: 0.0,
+                    grab_offset,
+                    camera: cursor.camera,
+                    group,
+                    screen_position: cursor.logical,
+                    world_position: cursor.world(),
+                });
+                return;
+            }
+            writer.start(
+                &mut commands,
+                (grabbed, grab_offset),
+                &group,
+                cursor.camera,
+                (cursor.logical, cursor.world()),
+                inputs,
+            );
+        }
+    }
+}
+
+pub(crate) fn awaitdrag(
+    mut commands: Commands,
+    mut q_draggable: Query<(Entity, &Draggable, &mut AwaitingDrag)>,
+    mut writer: DragWriter,
+    hit_test: DragHitTest,
+    hold_time: HoldTime,
+) {
+    let inputs = hit_test.drag_camera.input.inputs;
+
+    for (entity, draggable, mut awaiting) in q_draggable.iter_mut() {
+        let cursor = hit_test
+            .drag_camera
+            .cursor_for(Some(awaiting.camera))
+            .map(|(_, cursor)| cursor);
+        if draggable.enabled
+            && inputs.contains(draggable.required)
+            && !(inputs.intersects(draggable.disallowed))
+            && (!hold_time.settings.hold_in_bounds
+                || hit_test.is_under_cursor_for(entity, awaiting.camera))
+            && draggable.hold_slop.is_none_or(|slop| {
+                cursor
+                    .is_some_and(|cursor| cursor.logical.distance(awaiting.screen_position) <= slop)
+            })
+        {
+            let now = hold_time.elapsed();
+            awaiting.progress = awaiting.progress_at(now);
+            if now > awaiting.ends {
+                writer.start(
+                    &mut commands,
+                    (entity, awaiting.grab_offset),
+                    &awaiting.group,
+                    awaiting.camera,
+                    // Start from where the cursor is, which may have moved during the wait
+                    cursor.map_or(
+                        (awaiting.screen_position, awaiting.world_position),
+                        |cursor| (cursor.logical, cursor.world()),
+                    ),
+                    inputs,
+                );
+            }
+            continue;
+        }
+        let event = DragAwaitCancelled {
+            awaiting: entity,
+            inputs,
+        };
+        writer.ew_await_cancelled.write(event);
+        commands.trigger_targets(event, entity);
+        commands.entity(entity).remove::<AwaitingDrag>();
+    }
+}
+
+/// Components of a dragged entity that `dragging` moves it with.
+#[derive(QueryData)]
+#[query_data(mutable)]
+pub(crate) struct DraggedEntity {
+    entity: Entity,
+    child_of: Option<&'static ChildOf>,
+    transform: &'static mut Transform,
+    node: Option<&'static mut Node>,
+    visibility: Option<&'static mut Visibility>,
+    dragging: &'static mut Dragging,
+    draggable: &'static Draggable,
+    drag_offset: Option<&'static DragOffset>,
+    drag_z_index: Option<&'static DragZIndex>,
+    z_index: Option<&'static ZIndex>,
+    global_z_index: Option<&'static GlobalZIndex>,
+    drag_plane: Option<&'static DragPlane>,
+    drag_smoothing: Option<&'static DragSmoothing>,
+    drag_reparent: Option<&'static DragReparent>,
+    drag_nudge: Option<&'static DragNudge>,
+}
+
+/// Decides how dragged UI nodes are laid out.
+#[derive(SystemParam)]
+pub(crate) struct DragLayout<'w> {
+    settings: Res<'w, DragSettings>,
+    ui_scale: Res<'w, UiScale>,
+}
+
+pub(crate) fn dragging(
+    mut commands: Commands,
+    mut q_dragging: Query<DraggedEntity>,
+    receivers: ReceiverLookup,
+    drag_camera: DragCameraQuery,
+    mut writer: DragWriter,
+    layout: DragLayout,
+    mut time: DragTime,
+) {
+    let DragLayout { settings, ui_scale } = layout;
+    let inputs = drag_camera.input.inputs;
+    let delta = time.delta_secs();
+    for DraggedEntityItem {
+        entity,
+        child_of,
+        mut transform,
+        node: style,
+        visibility,
+        mut dragging,
+        draggable,
+        drag_offset,
+        drag_z_index,
+        z_index,
+        global_z_index,
+        drag_plane,
+        drag_smoothing,
+        drag_reparent,
+        drag_nudge,
+    } in q_dragging.iter_mut()
+    {
+        if inputs.modifiers() != dragging.inputs.modifiers() {
+            writer.ew_input_changed.write(DragInputChanged {
+                dragged: entity,
+                inputs,
+                previous: dragging.inputs,
+            });
+            dragging.inputs = inputs;
+        }
+
+        // While the cursor is outside of the window, the entity stays where it was last seen
+        if let Some((view, cursor)) = drag_camera.cursor_for(dragging.camera) {
+            dragging.camera = Some(view.entity);
+            let (last_logical, last_world) = dragging
+                .last_cursor
+                .replace((cursor.logical, cursor.world()))
+                .unwrap_or((cursor.logical, cursor.world()));
+            writer.ew_move.write(DragMove {
+                dragged: entity,
+                world_delta: cursor.world() - last_world,
+                logical_delta: cursor.logical - last_logical,
+                cursor: cursor.logical,
+                inputs,
+            });
+            // Entities that are only moved by nudging are held as if the cursor stayed where it grabbed them
+            let cursor = match drag_nudge.map(|drag_nudge| drag_nudge.mode) {
+                Some(NudgeMode::Replace) => {
+                    let anchor = *dragging.nudge_anchor.get_or_insert(cursor.logical);
+                    CursorPosition::from_logical(anchor, &view).unwrap_or(cursor)
+                }
+                _ => cursor,
+            };
+            let logical_position = cursor.logical;
+            let world_position = cursor.world();
+
+            // Fraction of the way to the cursor to move this frame
+            let smoothing = drag_smoothing.map(|smoothing| 1.0 - (-smoothing.lerp * delta).exp());
+            // Nudged entities are away from the cursor, so they are hit tested where they are held instead
+            let hit_test_entity = drag_smoothing.is_some_and(|smoothing| smoothing.hit_test_entity);
+            let nudged = dragging.nudge != Vec2::ZERO;
+            let mut hit_cursor = cursor;
+
+            // An explicit drag offset component takes precedence over the offset measured at grab time
+            let offset = match drag_offset {
+                None => dragging.grab_offset,
+                Some(drag_offset) if style.is_some() => {
+                    Vec3::new(drag_offset.x, drag_offset.y, 0.0)
+                }
+                Some(drag_offset) => world_drag_offset(drag_offset, &view, &cursor, drag_plane),
+            };
+            // Entities dragged along with a selection are hit tested at the point held like the grabbed entity's
+            let grouped = dragging.group_offset != Vec3::ZERO;
+            let hit_offset = offset + dragging.group_offset;
+
+            // UI entities may be kept in their container, depending on the settings
+            let reparent = style.is_none()
+                || drag_reparent.map_or(settings.reparent_ui, |reparent| reparent.0);
+            // Entities that don't follow the cursor are left entirely to the app
+            let follow = draggable.follow_cursor;
+
+            if follow && dragging.origin.is_none() {
+                dragging.origin = Some((*transform, style.as_deref().cloned()));
+            }
+
+            // Check if we need to reparent this entity to bypass container positioning
+            if follow && !dragging.reparented && child_of.is_some() && reparent {
+                // First frame of dragging - reparent to root
+                commands.entity(entity).remove::<ChildOf>();
+                dragging.reparented = true;
+                dragging.original_parent = child_of.map(|child_of| child_of.parent());
+
+                #[cfg(feature = "log")]
+                debug!(
+                    ?entity,
+                    original_parent = ?dragging.original_parent,
+                    "reparented dragged entity to root for direct positioning"
+                );
+            }
+
+            // Raise UI entities above the others once, for the whole drag
+            if follow && style.is_some() && dragging.saved_z_index.is_none() {
+                match drag_z_index.copied().unwrap_or(settings.dragged_z_index) {
+                    DragZIndex::Local(z) => {
+                        dragging.saved_z_index = Some(SavedZIndex::Local(z_index.copied()));
+                        commands.entity(entity).insert(ZIndex(z));
+                    }
+                    DragZIndex::Global(z) => {
+                        dragging.saved_z_index = Some(SavedZIndex::Global(global_z_index.copied()));
+                        commands.entity(entity).insert(GlobalZIndex(z));
+                    }
+                }
+            }
+
+            // Raise entities in the world towards the camera once, for the whole drag
+            if let (true, true, true, Some(z_boost), None) = (
+                follow,
+                style.is_none(),
+                drag_plane.is_none(),
+                draggable.z_boost,
+                dragging.saved_z,
+            ) {
+                dragging.saved_z = Some(transform.translation.z);
+                transform.translation.z += z_boost;
+            }
+
+            #[cfg(feature = "log")]
+            trace!(
+                ?entity,
+                ?logical_position,
+                ?world_position,
+                ?offset,
+                reparented = dragging.reparented,
+                is_ui_node = style.is_some(),
+                "positioning dragged entity"
+            );
+
+            // UI nodes are positioned with `left` and `top`, and everything else with its translation
+            let is_ui_node = style.is_some();
+            let previous = if is_ui_node {
+                dragging
+                    .last_position()
+                    .map(|position| position.extend(0.0))
+            } else {
+                Some(transform.translation)
+            };
+            if !follow {
+                // Left where it is for the app to move
+            } else if let Some(mut style) = style {
+                // UI is laid out from the top left corner of the camera's viewport, and `Val::Px` is scaled by
+                // `UiScale` on top of the window's scale factor
+                let viewport_min = view
+                    .camera
+                    .logical_viewport_rect()
+                    .map_or(Vec2::ZERO, |rect| rect.min);
+                let ui_position = (logical_position - viewport_min) / ui_scale.0;
+                let ui_nudge = Vec2::new(dragging.nudge.x, -dragging.nudge.y);
+                if dragging.reparented || child_of.is_none() {
+                    // Use absolute positioning at root level with component-based offsets
+                    style.position_type = PositionType::Absolute;
+                    let target = ui_position - offset.truncate() + ui_nudge;
+                    let position = match (smoothing, dragging.last_position()) {
+                        (Some(smoothing), Some(last)) => last.lerp(target, smoothing),
+                        _ => target,
+                    };
+                    style.left = Val::Px(position.x);
+                    style.top = Val::Px(position.y);
+                    dragging.record_position(position, time.elapsed_secs_f64());
+                    if hit_test_entity || grouped || nudged {
+                        let held = if hit_test_entity { position } else { target };
+                        let held = (held + hit_offset.truncate()) * ui_scale.0 + viewport_min;
+                        hit_cursor = CursorPosition::from_logical(held, &view).unwrap_or(cursor);
+                    }
+
+                    // Reset conflicting positioning properties
+                    style.right = Val::Auto;
+                    style.bottom = Val::Auto;
+                    style.margin = UiRect::all(Val::Px(0.0));
+
+                    // Ensure visibility and proper layering
+                    style.display = Display::Flex;
+
+                    #[cfg(feature = "log")]
+                    trace!(?entity, left = ?style.left, top = ?style.top, "ui positioning path");
+                } else {
+                    // Still in its container, so offset the node from where it was by how far the cursor moved,
+                    // which leaves the layout of its siblings alone
+                    let (cursor_start, node_start) =
+                        *dragging.container_start.get_or_insert_with(|| {
+                            let px = |val: Val| if let Val::Px(px) = val { px } else { 0.0 };
+                            (ui_position, Vec2::new(px(style.left), px(style.top)))
+                        });
+                    let target = node_start + ui_position - cursor_start + ui_nudge;
+                    let position = match (smoothing, dragging.last_position()) {
+                        (Some(smoothing), Some(last)) => last.lerp(target, smoothing),
+                        _ => target,
+                    };
+                    style.left = Val::Px(position.x);
+                    style.top = Val::Px(position.y);
+                    dragging.record_position(position, time.elapsed_secs_f64());
+                    if hit_test_entity || grouped || nudged {
+                        let held = if hit_test_entity { position } else { target };
+                        let held = ui_position
+                            + (held - target)
+                            + ui_nudge
+                            + dragging.group_offset.truncate();
+                        hit_cursor =
+                            CursorPosition::from_logical(held * ui_scale.0 + viewport_min, &view)
+                                .unwrap_or(cursor);
+                    }
+
+                    #[cfg(feature = "log")]
+                    trace!(?entity, left = ?style.left, top = ?style.top, "container positioning path");
+                }
+            } else if let Some(drag_plane) = drag_plane {
+                // Follow the cursor's ray along the plane
+                if let Some(hit) = drag_plane.intersect(cursor.ray) {
+                    transform.translation = match smoothing {
+                        Some(smoothing) => transform.translation.lerp(hit - offset, smoothing),
+                        None => hit - offset,
+                    };
+                    if hit_test_entity || grouped {
+                        let held = if hit_test_entity {
+                            transform.translation
+                        } else {
+                            hit - offset
+                        };
+                        hit_cursor =
+                            CursorPosition::from_world(held + hit_offset, &view).unwrap_or(cursor);
+                    }
+                    #[cfg(feature = "log")]
+                    trace!(?entity, translation = ?transform.translation, "plane positioning path");
+                }
+            } else {
+                // For world objects, use world position directly
+                let target = world_position - offset.truncate() + dragging.nudge;
+                let position = match (smoothing, dragging.last_position()) {
+                    (Some(smoothing), Some(last)) => last.lerp(target, smoothing),
+                    _ => target,
+                };
+                transform.translation = Vec3::new(position.x, position.y, transform.translation.z);
+                dragging.record_position(position, time.elapsed_secs_f64());
+                if hit_test_entity || grouped || nudged {
+                    let held = if hit_test_entity { position } else { target };
+                    hit_cursor = CursorPosition::from_world(
+                        (held + hit_offset.truncate()).extend(transform.translation.z),
+                        &view,
+                    )
+                    .unwrap_or(cursor);
+                }
+                #[cfg(feature = "log")]
+                trace!(?entity, translation = ?transform.translation, "world positioning path");
+            }
+
+            let position = if is_ui_node {
+                dragging
+                    .last_position()
+                    .map(|position| position.extend(0.0))
+            } else {
+                Some(transform.translation)
+            };
+            if let (Some(previous), Some(position)) = (previous, position) {
+                if position != previous {
+                    writer.ew_moved.write(DragMoved {
+                        dragged: entity,
+                        position,
+                        delta: position - previous,
+                        inputs,
+                    });
+                }
+            }
+
+            // Ensure dragged entity is visible
+            if let (true, Some(mut visibility)) = (follow, visibility) {
+                *visibility = Visibility::Visible;
+            }
+
+            let (hovering, hovering_full) = receivers.lookup(&hit_cursor, entity);
+            dragging.hovering_full = hovering_full;
+            if let Some(receiver) = hovering {
+                if dragging.hovering != Some(receiver) {
+                    writer.ew_hover.write(HoveredChange {
+                        hovered: entity,
+                        prevreceiver: dragging.hovering,
+                        receiver: Some(receiver),
+                        screen_position: Some(logical_position),
+                        world_position: Some(world_position),
+                        receiver_local: receivers.index.local_position(receiver, &hit_cursor),
+                        inputs,
+                    });
+                    dragging.hovering = Some(receiver);
+                    dragging.start_hover(time.elapsed_secs_f64());
+                    writer.active_drag.set_hovering(entity, dragging.hovering);
+                }
+            } else if dragging.hovering.is_some() {
+                writer.ew_hover.write(HoveredChange::left(
+                    entity,
+                    dragging.hovering,
+                    Some(&cursor),
+                    inputs,
+                ));
+                dragging.hovering = None;
+                dragging.start_hover(time.elapsed_secs_f64());
+                writer.active_drag.set_hovering(entity, None);
+            }
+
+            let reorder = dragging
+                .hovering
+                .and_then(|receiver| receivers.insertion_index(receiver, &hit_cursor, entity));
+            if reorder != dragging.reorder {
+                if let Some((container, index)) = reorder {
+                    writer.ew_hover.ew_reorder.write(ReorderHover {
+                        dragged: entity,
+                        container,
+                        index,
+                        inputs,
+                    });
+                }
+                dragging.reorder = reorder;
+            }
+
+            if let Some(receiver) = dragging.hovering {
+                let elapsed = time.elapsed_secs_f64() - dragging.hover_started;
+                if receivers
+                    .next_dwell(receiver, dragging.dwells)
+                    .is_some_and(|next| elapsed >= next)
+                {
+                    writer.ew_dwell.write(HoverDwell {
+                        dragged: entity,
+                        receiver,
+                        elapsed,
+                    });
+                    dragging.dwells += 1;
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn drop(
+    mut commands: Commands,
+    receivers: ReceiverLookup,
+    q_dragging: Query<(Entity, &Draggable, &Dragging, Option<&DragSmoothing>)>,
+    targets: DropTargets,
+    drag_camera: DragCameraQuery,
+    mut writer: DragWriter,
+) {
+    let inputs = drag_camera.input.inputs;
+    if q_dragging.is_empty() {
+        return;
+    }
+    for (entity, draggable, dragging, drag_smoothing) in q_dragging.iter() {
+        // The cursor is unknown if the buttons were released outside of the window
+        let cursor = drag_camera
+            .cursor_for(dragging.camera)
+            .map(|(_, cursor)| cursor);
+        if !draggable.enabled {
+            writer.cancel(
+                &mut commands,
+                entity,
+                draggable,
+                dragging,
+                cursor.as_ref(),
+                inputs,
+            );
+            continue;
+        }
+        if !dragging.lost
+            && (dragging.manual || !draggable.release.released(draggable.required, inputs))
+        {
+            continue;
+        }
+        // Entities that are hit tested away from the cursor, because they are smoothed or dragged along with
+        // a selection, were last seen over the receiver they hover
+        let hit_test_entity = drag_smoothing.is_some_and(|smoothing| smoothing.hit_test_entity);
+        let received = match cursor {
+            None => None,
+            Some(_)
+                if hit_test_entity
+                    || dragging.group_offset != Vec3::ZERO
+                    || dragging.nudge != Vec2::ZERO =>
+            {
+                dragging.hovering
+            }
+            Some(cursor) => receivers.receiver_at(&cursor, entity),
+        };
+        let received = validate_drop(&targets.q_entities, entity, received);
+        let cancelled = match (cursor, received) {
+            (None, _) if draggable.outside_release == OutsideRelease::Cancel => true,
+            (_, None) => draggable.drop_outside == DropOutside::Cancel,
+            _ => false,
+        };
+        if cancelled {
+            writer.cancel(
+                &mut commands,
+                entity,
+                draggable,
+                dragging,
+                cursor.as_ref(),
+                inputs,
+            );
+            continue;
+        }
+        writer.ew_hover.write(HoveredChange::left(
+            entity,
+            dragging.hovering,
+            cursor.as_ref(),
+            inputs,
+        ));
+        let received_local = received
+            .zip(cursor)
+            .and_then(|(received, cursor)| targets.index.local_position(received, &cursor));
+        let displaced = apply_receiver_mode(
+            &mut commands,
+            &targets.q_entities,
+            entity,
+            dragging,
+            received,
+        );
+        writer.ew_dropped.write(Dropped {
+            dropped: entity,
+            received,
+            received_local,
+            screen_position: cursor.map(|cursor| cursor.logical),
+            world_position: cursor.map(|cursor| cursor.world()),
+            index: dragging.reorder_index(received),
+            displaced,
+            original_parent: dragging.original_parent,
+            inputs,
+        });
+        let returned = received.is_none() && draggable.drop_outside == DropOutside::Return;
+        writer.end(
+            &mut commands,
+            entity,
+            draggable,
+            dragging,
+            received,
+            returned,
+        );
+        if returned {
+            return_to_origin(&mut commands, entity, dragging);
+            continue;
+        }
+        start_inertia(&mut commands, entity, draggable, dragging);
+    }
+}
+
+/// Triggers the events that concern specific entities for observers of those entities, so they can be handled with
+/// `EntityCommands::observe` rather than filtering an `EventReader`. `Dragged` is triggered as soon as the drag starts
+/// instead, see `DragSystem`.
+pub(crate) fn trigger_observers(
+    mut commands: Commands,
+    mut er_hover: EventReader<HoveredChange>,
+    mut er_exit: EventReader<HoverExit>,
+    mut er_enter: EventReader<HoverEnter>,
+    mut er_dropped: EventReader<Dropped>,
+    mut er_dwell: EventReader<HoverDwell>,
+    mut er_input_changed: EventReader<DragInputChanged>,
+) {
+    for event in er_hover.read() {
+        let targets: Vec<Entity> = std::iter::once(event.hovered)
+            .chain(event.receiver)
+            .chain(event.prevreceiver)
+            .collect();
+        commands.trigger_targets(*event, targets);
+    }
+    for event in er_exit.read() {
+        commands.trigger_targets(*event, [event.dragged, event.receiver]);
+    }
+    for event in er_enter.read() {
+        commands.trigger_targets(*event, [event.dragged, event.receiver]);
+    }
+    for event in er_dropped.read() {
+        let targets: Vec<Entity> = std::iter::once(event.dropped)
+            .chain(event.received)
+            .collect();
+        commands.trigger_targets(*event, targets);
+    }
+    for event in er_dwell.read() {
+        commands.trigger_targets(*event, [event.dragged, event.receiver]);
+    }
+    for event in er_input_changed.read() {
+        commands.trigger_targets(*event, event.dragged);
+    }
+}
+
+pub(crate) fn drag_commands(
+    mut commands: Commands,
+    mut er_commands: EventReader<DragCommand>,
+    q_draggable: Query<(HitTarget, &Draggable, Option<&DragPlane>, Option<&Dragging>)>,
+    targets: DropTargets,
+    drag_camera: DragCameraQuery,
+    mut writer: DragWriter,
+    settings: Res<DragSettings>,
+) {
+    let inputs = drag_camera.input.inputs;
+    for command in er_commands.read() {
+        match *command {
+            DragCommand::Start(entity) => {
+                let Ok((target, draggable, drag_plane, None)) = q_draggable.get(entity) else {
+                    continue;
+                };
+                if !draggable.enabled {
+                    continue;
+                }
+                if writer.active_drag.contains(entity) {
+                    continue;
+                }
+                // There is no grab point, so the entity is held as if grabbed where the cursor is, or centered on it
+                // while the cursor isn't over a window
+                let cursor = drag_camera.cursor().map(|(_, cursor)| cursor);
+                let grab_offset = match cursor {
+                    Some(cursor) => {
+                        get_grab_offset(settings.default_grab_mode, &target, drag_plane, &cursor)
+                    }
+                    None => target.computed_node.map_or(Vec3::ZERO, |computed_node| {
+                        (computed_node.size() * computed_node.inverse_scale_factor() / 2.0)
+                            .extend(0.0)
+                    }),
+                };
+                let mut dragging = Dragging::new(grab_offset, None, inputs);
+                dragging.manual = true;
+                let event = Dragged {
+                    dragged: entity,
+                    screen_position: cursor.map(|cursor| cursor.logical),
+                    world_position: cursor.map(|cursor| cursor.world()),
+                    grab_offset,
+                    inputs,
+                };
+                writer.ew_dragged.write(event);
+                writer.active_drag.start(entity);
+                commands
+                    .entity(entity)
+                    .insert(dragging)
+                    .remove::<(AwaitingDrag, DragInertia)>();
+                commands.trigger_targets(event, entity);
+            }
+            DragCommand::Drop(entity) => {
+                let Ok((_, draggable, _, Some(dragging))) = q_draggable.get(entity) else {
+                    continue;
+                };
+                let received = validate_drop(&targets.q_entities, entity, dragging.hovering);
+                let cursor = drag_camera
+                    .cursor_for(dragging.camera)
+                    .map(|(_, cursor)| cursor);
+                if let (None, DropOutside::Cancel) = (received, draggable.drop_outside) {
+                    writer.cancel(
+                        &mut commands,
+                        entity,
+                        draggable,
+                        dragging,
+                        cursor.as_ref(),
+                        inputs,
+                    );
+                    continue;
+                }
+                let received_local = received
+                    .zip(cursor)
+                    .and_then(|(received, cursor)| targets.index.local_position(received, &cursor));
+                let displaced = apply_receiver_mode(
+                    &mut commands,
+                    &targets.q_entities,
+                    entity,
+                    dragging,
+                    received,
+                );
+                writer.ew_hover.write(HoveredChange::left(
+                    entity,
+                    dragging.hovering,
+                    cursor.as_ref(),
+                    inputs,
+                ));
+                writer.ew_dropped.write(Dropped {
+                    dropped: entity,
+                    received,
+                    received_local,
+                    screen_position: cursor.map(|cursor| cursor.logical),
+                    world_position: cursor.map(|cursor| cursor.world()),
+                    index: dragging.reorder_index(received),
+                    displaced,
+                    original_parent: dragging.original_parent,
+                    inputs,
+                });
+                let returned = received.is_none() && draggable.drop_outside == DropOutside::Return;
+                writer.end(
+                    &mut commands,
+                    entity,
+                    draggable,
+                    dragging,
+                    received,
+                    returned,
+                );
+                if returned {
+                    return_to_origin(&mut commands, entity, dragging);
+                    continue;
+                }
+                start_inertia(&mut commands, entity, draggable, dragging);
+            }
+            DragCommand::Cancel(entity) => {
+                let Ok((_, draggable, _, Some(dragging))) = q_draggable.get(entity) else {
+                    continue;
+                };
+                let cursor = drag_camera
+                    .cursor_for(dragging.camera)
+                    .map(|(_, cursor)| cursor);
+                writer.cancel(
+                    &mut commands,
+                    entity,
+                    draggable,
+                    dragging,
+                    cursor.as_ref(),
+                    inputs,
+                );
+            }
+        }
+    }
+}
+
+/// Puts an entity dropped without a receiver back where it was before it was dragged, for `DropOutside::Return`.
+/// Its parent is restored by `DragWriter::end`.
+pub(crate) fn return_to_origin(commands: &mut Commands, entity: Entity, dragging: &Dragging) {
+    let Some((transform, node)) = dragging.origin.clone() else {
+        return;
+    };
+    let mut entity_commands = commands.entity(entity);
+    entity_commands.insert(transform);
+    if let Some(node) = node {
+        entity_commands.insert(node);
+    }
+}
+
+/// Moves dragged entities with `DragNudge` by the arrow keys and the left stick of every gamepad.
+pub(crate) fn nudge(
+    mut q_dragging: Query<(Entity, &mut Dragging, &DragNudge)>,
+    mut ew_nudged: EventWriter<DragNudged>,
+    input: Res<DragInputState>,
+    mut time: DragTime,
+) {
+    let delta = time.delta_secs();
+    let direction = input.nudge;
+    if direction == Vec2::ZERO {
+        return;
+    }
+    let inputs = input.inputs;
+    for (dragged, mut dragging, drag_nudge) in q_dragging.iter_mut() {
+        let delta = direction * drag_nudge.speed * delta;
+        dragging.nudge += delta;
+        ew_nudged.write(DragNudged {
+            dragged,
+            delta,
+            inputs,
+        });
+    }
+}
+
+/// Ends drags that lost the cursor or went further than their `Draggable` allows, see `Draggable::on_lost` and
+/// `Draggable::max_drag_distance`. Drags that are dropped are only marked here, and dropped by `drop`.
+/// Finds the windows that lost the cursor, by losing focus or the cursor leaving them.
+#[derive(SystemParam)]
+pub(crate) struct LostWindows<'w, 's> {
+    q_windows: Query<'w, 's, &'static Window>,
+    er_focused: EventReader<'w, 's, WindowFocused>,
+    er_left: EventReader<'w, 's, CursorLeft>,
+}
+
+impl LostWindows<'_, '_> {
+    /// Windows that lost the cursor since the system last ran.
+    fn read(&mut self) -> Vec<Entity> {
+        self.er_focused
+            .read()
+            .filter(|event| !event.focused)
+            .map(|event| event.window)
+            .chain(self.er_left.read().map(|event| event.window))
+            .collect()
+    }
+
+    fn is_focused(&self, window: Entity) -> bool {
+        self.q_windows
+            .get(window)
+            .is_ok_and(|window| window.focused)
+    }
+}
+
+pub(crate) fn end_lost_drags(
+    mut commands: Commands,
+    mut q_dragging: Query<(Entity, &Draggable, &mut Dragging)>,
+    mut windows: LostWindows,
+    drag_camera: DragCameraQuery,
+    mut writer: DragWriter,
+) {
+    let inputs = drag_camera.input.inputs;
+    // Windows that lost the cursor this frame, so that drags don't wait a frame to notice
+    let lost_windows = windows.read();
+    for (entity, draggable, mut dragging) in q_dragging.iter_mut() {
+        let window = dragging
+            .camera
+            .and_then(|camera| drag_camera.view(camera))
+            .map(|view| view.window);
+        let cursor = drag_camera
+            .cursor_for(dragging.camera)
+            .filter(|(view, _)| windows.is_focused(view.window))
+            .map(|(_, cursor)| cursor);
+        let too_far = match cursor {
+            Some(cursor) => {
+                dragging.lost_frames = 0;
+                let grabbed = *dragging.grab_screen_position.get_or_insert(cursor.logical);
+                draggable
+                    .max_drag_distance
+                    .is_some_and(|max| cursor.logical.distance(grabbed) > max)
+            }
+            None => {
+                dragging.lost_frames += 1;
+                false
+            }
+        };
+        let lost = !dragging.manual
+            && (dragging.lost_frames > 1
+                || window.is_some_and(|window| lost_windows.contains(&window)));
+        let cancel = match (lost, draggable.on_lost) {
+            (true, LostCursor::Drop) => {
+                dragging.lost = true;
+                too_far
+            }
+            (true, LostCursor::Cancel) => true,
+            _ => too_far,
+        };
+        if !cancel {
+            continue;
+        }
+        writer.cancel(
+            &mut commands,
+            entity,
+            draggable,
+            &dragging,
+            cursor.as_ref(),
+            inputs,
+        );
+    }
+}
+
+/// Sends `DragScroll` for every dragged entity when the mouse wheel is scrolled.
+pub(crate) fn drag_scroll(
+    mut er_wheel: EventReader<MouseWheel>,
+    q_dragging: Query<Entity, With<Dragging>>,
+    mut ew_scroll: EventWriter<DragScroll>,
+    input: Res<DragInputState>,
+) {
+    // Scrolling is read regardless so that it isn't reported once a drag starts
+    for wheel in er_wheel.read() {
+        for dragged in q_dragging.iter() {
+            ew_scroll.write(DragScroll {
+                dragged,
+                delta: Vec2::new(wheel.x, wheel.y),
+                unit: wheel.unit,
+                inputs: input.inputs,
+            });
+        }
+    }
+}
+
+/// Forgets entities whose `Dragging` component was removed or despawned outside of the plugin.
+pub(crate) fn clean_active_drag(
+    mut active_drag: ResMut<ActiveDrag>,
+    mut removed: RemovedComponents<Dragging>,
+    q_dragging: Query<(), With<Dragging>>,
+) {
+    for entity in removed.read() {
+        if !q_dragging.contains(entity) {
+            active_drag.end(entity);
+        }
+    }
+}
+
+pub(crate) fn inertia(
+    mut commands: Commands,
+    mut q_inertia: Query<
+        (Entity, &mut DragInertia, &mut Transform, Option<&mut Node>),
+        Without<Dragging>,
+    >,
+    mut time: DragTime,
+) {
+    let delta = time.delta_secs();
+    for (entity, mut inertia, mut transform, style) in q_inertia.iter_mut() {
+        let step = inertia.velocity * delta;
+        if let Some(mut style) = style {
+            // Only nodes that are still absolutely positioned from dragging can keep sliding
+            if let (Val::Px(left), Val::Px(top)) = (style.left, style.top) {
+                style.left = Val::Px(left + step.x);
+                style.top = Val::Px(top + step.y);
+            }
+        } else {
+            transform.translation += step.extend(0.0);
+        }
+
+        let decay = (-inertia.damping * delta).exp();
+        inertia.velocity *= decay;
+        if inertia.velocity.length() < DragInertia::MIN_SPEED {
+            commands.entity(entity).remove::<DragInertia>();
+        }
+    }
+}
+
+pub(crate) fn start_inertia(
+    commands: &mut Commands,
+    entity: Entity,
+    draggable: &Draggable,
+    dragging: &Dragging,
+) {
+    let velocity = dragging.velocity();
+    if draggable.inertia && velocity.length() >= DragInertia::MIN_SPEED {
+        commands.entity(entity).insert(DragInertia {
+            velocity,
+            damping: DragInertia::DEFAULT_DAMPING,
+        });
+    }
+}
+
+pub(crate) fn restore_z_index(commands: &mut Commands, entity: Entity, dragging: &Dragging) {
+    if let Some(z) = dragging.saved_z {
+        commands
+            .entity(entity)
+            .entry::<Transform>()
+            .and_modify(move |mut transform| transform.translation.z = z);
+    }
+    match dragging.saved_z_index {
+        Some(SavedZIndex::Local(Some(z_index))) => {
+            commands.entity(entity).insert(z_index);
+        }
+        Some(SavedZIndex::Local(None)) => {
+            commands.entity(entity).remove::<ZIndex>();
+        }
+        Some(SavedZIndex::Global(Some(global_z_index))) => {
+            commands.entity(entity).insert(global_z_index);
+        }
+        Some(SavedZIndex::Global(None)) => {
+            commands.entity(entity).remove::<GlobalZIndex>();
+        }
+        None => {}
+    }
+}
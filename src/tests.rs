@@ -0,0 +1,1189 @@
+use super::*;
+use bevy::{
+    ecs::system::SystemState,
+    render::{
+        camera::{camera_system, ManualTextureViews},
+        primitives::Aabb,
+    },
+    time::TimeUpdateStrategy,
+    transform::TransformPlugin,
+    window::{
+        PrimaryWindow, WindowCreated, WindowResized, WindowResolution, WindowScaleFactorChanged,
+    },
+};
+use std::time::Duration;
+
+use crate::{
+    hit_test::{
+        get_bounds, get_grab_offset, is_in_bounds, select_candidate, Bounds, CursorPosition,
+        DrawOrder, HitTarget, SpriteSizeCache, SpriteSizes,
+    },
+    receivers::{kinds_match, ReceiverIndex, ReceiverLookup},
+};
+
+const SCALE_FACTORS: [f32; 3] = [1.0, 1.5, 2.0];
+
+/// Cursor at a logical position on a window with the given scale factor.
+fn cursor_at(logical: Vec2, scale_factor: f32) -> CursorPosition {
+    CursorPosition {
+        logical,
+        physical: logical * scale_factor,
+        ray: Ray3d::new(Vec3::ZERO, Dir3::NEG_Z),
+        camera: Entity::PLACEHOLDER,
+    }
+}
+
+/// World with a 100 by 100 logical pixel UI node whose top left corner is at (150, 150),
+/// laid out in physical pixels like bevy does at the given scale factor.
+fn ui_world(scale_factor: f32) -> (World, Entity) {
+    let mut world = hit_test_world();
+    let node = world
+        .spawn((
+            ComputedNode {
+                size: Vec2::splat(100.0 * scale_factor),
+                inverse_scale_factor: scale_factor.recip(),
+                ..default()
+            },
+            GlobalTransform::from_translation(Vec3::new(200.0, 200.0, 0.0) * scale_factor),
+        ))
+        .id();
+    (world, node)
+}
+
+fn is_hit(world: &mut World, entity: Entity, cursor: CursorPosition) -> bool {
+    let mut state = SystemState::<(Query<HitTarget>, SpriteSizes)>::new(world);
+    let (q_targets, sprite_sizes) = state.get(world);
+    is_in_bounds(&q_targets.get(entity).unwrap(), &sprite_sizes, &cursor)
+}
+
+/// World with the resources hit testing reads.
+fn hit_test_world() -> World {
+    let mut world = World::new();
+    world.init_resource::<SpriteSizeCache>();
+    world.init_resource::<Assets<Image>>();
+    world.init_resource::<Assets<TextureAtlasLayout>>();
+    world
+}
+
+fn grab_offset(world: &mut World, entity: Entity, mode: GrabMode, cursor: CursorPosition) -> Vec3 {
+    let mut state = SystemState::<Query<HitTarget>>::new(world);
+    let q_targets = state.get(world);
+    get_grab_offset(mode, &q_targets.get(entity).unwrap(), None, &cursor)
+}
+
+/// App running `DragPlugin` without rendering or a real window. Its 800 by 600 window looks at the world through
+/// a 2D camera centered on the origin, so the middle of the window is over the origin. Input is simulated with
+/// `move_cursor`, `press` and `release`, which each run a frame.
+fn headless_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, TransformPlugin, DragPlugin::default()));
+    headless_app_from(app)
+}
+
+/// Sets up a headless app to which the plugins were already added.
+fn headless_app_from(mut app: App) -> App {
+    app.init_resource::<ButtonInput<MouseButton>>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<Assets<Image>>()
+        .init_resource::<Assets<TextureAtlasLayout>>()
+        .init_resource::<ManualTextureViews>()
+        .init_resource::<UiScale>()
+        .add_event::<AssetEvent<Image>>()
+        .add_event::<WindowCreated>()
+        .add_event::<WindowResized>()
+        .add_event::<WindowScaleFactorChanged>()
+        // Cameras are otherwise given their viewport by the render plugin
+        .add_systems(PreUpdate, camera_system);
+    app.world_mut().spawn((
+        Window {
+            resolution: WindowResolution::new(800.0, 600.0),
+            ..default()
+        },
+        PrimaryWindow,
+    ));
+    app.world_mut().spawn((
+        Camera::default(),
+        Projection::Orthographic(OrthographicProjection::default_2d()),
+    ));
+    app.update();
+    app
+}
+
+fn move_cursor(app: &mut App, logical: Vec2) {
+    let mut q_windows = app.world_mut().query::<&mut Window>();
+    let mut window = q_windows.single_mut(app.world_mut()).unwrap();
+    window.set_cursor_position(Some(logical));
+    app.update();
+}
+
+fn press(app: &mut App, button: MouseButton) {
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(button);
+    app.update();
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .clear();
+}
+
+fn release(app: &mut App, button: MouseButton) {
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .release(button);
+    app.update();
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .clear();
+}
+
+/// Events of the given type sent since they were last checked. Events aren't cleared every frame without
+/// `FixedUpdate` running, so they are drained instead.
+fn events<E: Event>(app: &mut App) -> Vec<E> {
+    app.world_mut()
+        .resource_mut::<Events<E>>()
+        .drain()
+        .collect()
+}
+
+fn square(position: Vec2) -> (Sprite, Transform) {
+    (
+        Sprite::from_color(Color::WHITE, Vec2::splat(100.0)),
+        Transform::from_translation(position.extend(0.0)),
+    )
+}
+
+#[test]
+fn entity_is_dragged_onto_a_receiver() {
+    let mut app = headless_app();
+    let card = app
+        .world_mut()
+        .spawn((square(Vec2::ZERO), Draggable::default()))
+        .id();
+    let slot = app
+        .world_mut()
+        .spawn((square(Vec2::new(200.0, 0.0)), Receiver))
+        .id();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    let dragged = events::<Dragged>(&mut app);
+    assert_eq!(dragged.len(), 1);
+    assert_eq!(dragged[0].dragged, card);
+
+    move_cursor(&mut app, Vec2::new(600.0, 300.0));
+    let hovered = events::<HoveredChange>(&mut app);
+    assert_eq!(hovered.len(), 1);
+    assert_eq!(hovered[0].receiver, Some(slot));
+    let translation = app.world().get::<Transform>(card).unwrap().translation;
+    assert_eq!(translation.truncate(), Vec2::new(200.0, 0.0));
+
+    release(&mut app, MouseButton::Left);
+    let dropped = events::<Dropped>(&mut app);
+    assert_eq!(dropped.len(), 1);
+    assert_eq!(dropped[0].received, Some(slot));
+    assert!(app.world().get::<Dragging>(card).is_none());
+}
+
+#[test]
+fn receivers_are_hovered_where_they_moved_to() {
+    let mut app = headless_app();
+    let card = app
+        .world_mut()
+        .spawn((square(Vec2::ZERO), Draggable::default()))
+        .id();
+    let slot = app
+        .world_mut()
+        .spawn((square(Vec2::new(200.0, 0.0)), Receiver))
+        .id();
+    app.update();
+
+    // Moved after the index was built, alongside one added to it
+    app.world_mut()
+        .get_mut::<Transform>(slot)
+        .unwrap()
+        .translation
+        .x = -200.0;
+    let other_slot = app
+        .world_mut()
+        .spawn((square(Vec2::new(200.0, 0.0)), Receiver))
+        .id();
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    assert!(app.world().get::<Dragging>(card).is_some());
+
+    move_cursor(&mut app, Vec2::new(200.0, 300.0));
+    let hovered = events::<HoveredChange>(&mut app);
+    assert_eq!(hovered.last().unwrap().receiver, Some(slot));
+    move_cursor(&mut app, Vec2::new(600.0, 300.0));
+    let hovered = events::<HoveredChange>(&mut app);
+    assert_eq!(hovered.last().unwrap().receiver, Some(other_slot));
+}
+
+#[test]
+fn awaited_drag_is_cancelled_when_released_early_or_left() {
+    let mut app = headless_app();
+    let card = app
+        .world_mut()
+        .spawn((
+            square(Vec2::ZERO),
+            Draggable {
+                minimum_held: Some(10.0),
+                ..default()
+            },
+        ))
+        .id();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    assert_eq!(events::<DragAwait>(&mut app).len(), 1);
+    assert!(app.world().get::<AwaitingDrag>(card).is_some());
+
+    release(&mut app, MouseButton::Left);
+    assert_eq!(events::<DragAwaitCancelled>(&mut app).len(), 1);
+    assert!(app.world().get::<AwaitingDrag>(card).is_none());
+
+    press(&mut app, MouseButton::Left);
+    assert!(app.world().get::<AwaitingDrag>(card).is_some());
+    move_cursor(&mut app, Vec2::new(700.0, 300.0));
+    assert_eq!(events::<DragAwaitCancelled>(&mut app).len(), 1);
+    assert!(app.world().get::<AwaitingDrag>(card).is_none());
+    assert!(events::<Dragged>(&mut app).is_empty());
+
+    app.world_mut()
+        .resource_mut::<DragSettings>()
+        .hold_in_bounds = false;
+    release(&mut app, MouseButton::Left);
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    move_cursor(&mut app, Vec2::new(700.0, 300.0));
+    assert!(app.world().get::<AwaitingDrag>(card).is_some());
+}
+
+#[test]
+fn systems_after_the_drop_set_read_dropped_in_the_same_frame() {
+    #[derive(Resource, Default)]
+    struct DropCount(usize);
+
+    let mut app = headless_app();
+    app.init_resource::<DropCount>().add_systems(
+        Update,
+        (|mut er_drop: EventReader<Dropped>, mut count: ResMut<DropCount>| {
+            count.0 += er_drop.read().count();
+        })
+        .after(DragSystem::Drop),
+    );
+    app.world_mut()
+        .spawn((square(Vec2::ZERO), Draggable::default()));
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    release(&mut app, MouseButton::Left);
+    assert_eq!(app.world().resource::<DropCount>().0, 1);
+}
+
+#[test]
+fn entity_dropped_without_a_receiver_returns_or_cancels() {
+    for drop_outside in [DropOutside::Return, DropOutside::Cancel] {
+        let mut app = headless_app();
+        let card = app
+            .world_mut()
+            .spawn((
+                square(Vec2::ZERO),
+                Draggable {
+                    drop_outside,
+                    ..default()
+                },
+            ))
+            .id();
+
+        move_cursor(&mut app, Vec2::new(400.0, 300.0));
+        press(&mut app, MouseButton::Left);
+        move_cursor(&mut app, Vec2::new(600.0, 300.0));
+        release(&mut app, MouseButton::Left);
+        let translation = app.world().get::<Transform>(card).unwrap().translation;
+        match drop_outside {
+            DropOutside::Return => {
+                assert_eq!(events::<Dropped>(&mut app).len(), 1);
+                assert_eq!(translation, Vec3::ZERO);
+            }
+            _ => {
+                assert!(events::<Dropped>(&mut app).is_empty());
+                assert_eq!(events::<DragCancelled>(&mut app).len(), 1);
+                assert_eq!(translation.truncate(), Vec2::new(200.0, 0.0));
+            }
+        }
+    }
+}
+
+#[test]
+fn virtual_hold_clock_stops_while_paused() {
+    let mut app = headless_app();
+    app.world_mut().resource_mut::<DragSettings>().hold_clock = HoldClock::Virtual;
+    let card = app
+        .world_mut()
+        .spawn((square(Vec2::ZERO), Draggable::with_hold(0.0)))
+        .id();
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(app.world().get::<AwaitingDrag>(card).is_some());
+
+    app.world_mut().resource_mut::<Time<Virtual>>().unpause();
+    app.update();
+    app.update();
+    assert!(app.world().get::<Dragging>(card).is_some());
+}
+
+#[test]
+fn drags_follow_inputs_filled_by_another_system() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        DragPlugin {
+            default_input: false,
+            ..default()
+        },
+    ));
+    let mut app = headless_app_from(app);
+    let window = app
+        .world_mut()
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .single(app.world())
+        .unwrap();
+    let card = app
+        .world_mut()
+        .spawn((
+            square(Vec2::ZERO),
+            Draggable::default(),
+            DragNudge::default(),
+        ))
+        .id();
+
+    // The mouse isn't read at all
+    press(&mut app, MouseButton::Left);
+    assert!(events::<Dragged>(&mut app).is_empty());
+
+    *app.world_mut().resource_mut::<DragInputState>() = DragInputState {
+        inputs: InputFlags::LeftClick,
+        cursor: Some((window, Vec2::new(400.0, 300.0))),
+        ..default()
+    };
+    app.update();
+    assert!(app.world().get::<Dragging>(card).is_some());
+
+    // Nor are the arrow keys
+    app.world_mut().resource_mut::<DragInputState>().nudge = Vec2::X;
+    app.update();
+    let nudged = events::<DragNudged>(&mut app);
+    assert!(nudged.last().is_some_and(|nudged| nudged.delta.x > 0.0));
+}
+
+#[test]
+fn movement_is_measured_in_real_time_in_any_schedule() {
+    #[derive(ScheduleLabel, Clone, Debug, PartialEq, Eq, Hash)]
+    struct Twice;
+
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        TransformPlugin,
+        DragPlugin::in_schedule(Twice),
+    ))
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+        100,
+    )))
+    .add_systems(Update, |world: &mut World| {
+        world.run_schedule(Twice);
+        world.run_schedule(Twice);
+    });
+    let mut app = headless_app_from(app);
+    let card = app
+        .world_mut()
+        .spawn((
+            square(Vec2::ZERO),
+            DragInertia {
+                velocity: Vec2::new(100.0, 0.0),
+                damping: 0.0,
+            },
+        ))
+        .id();
+
+    app.update();
+    let translation = app.world().get::<Transform>(card).unwrap().translation;
+    assert_eq!(translation, Vec3::new(10.0, 0.0, 0.0));
+}
+
+#[test]
+fn drag_is_cancelled_when_it_goes_too_far_or_loses_the_cursor() {
+    let mut app = headless_app();
+    let card = app
+        .world_mut()
+        .spawn((
+            square(Vec2::ZERO),
+            Draggable {
+                max_drag_distance: Some(100.0),
+                on_lost: LostCursor::Cancel,
+                ..default()
+            },
+        ))
+        .id();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    move_cursor(&mut app, Vec2::new(550.0, 300.0));
+    assert_eq!(events::<DragCancelled>(&mut app).len(), 1);
+    assert!(app.world().get::<Dragging>(card).is_none());
+    // Coming back over the entity with the button still held doesn't pick it up again
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    assert!(app.world().get::<Dragging>(card).is_none());
+
+    release(&mut app, MouseButton::Left);
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    assert!(app.world().get::<Dragging>(card).is_some());
+    let mut q_windows = app.world_mut().query::<&mut Window>();
+    let mut window = q_windows.single_mut(app.world_mut()).unwrap();
+    window.set_cursor_position(None);
+    app.update();
+    assert!(app.world().get::<Dragging>(card).is_some());
+    app.update();
+    assert_eq!(events::<DragCancelled>(&mut app).len(), 1);
+    assert!(app.world().get::<Dragging>(card).is_none());
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    assert!(app.world().get::<Dragging>(card).is_none());
+}
+
+#[test]
+fn cancel_command_is_not_undone_while_the_button_is_held() {
+    let mut app = headless_app();
+    let card = app
+        .world_mut()
+        .spawn((square(Vec2::ZERO), Draggable::default()))
+        .id();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    assert!(app.world().get::<Dragging>(card).is_some());
+    app.world_mut().send_event(DragCommand::Cancel(card));
+    app.update();
+    assert_eq!(events::<DragCancelled>(&mut app).len(), 1);
+    assert!(app.world().get::<Dragging>(card).is_none());
+
+    // The button is still held over the entity
+    app.update();
+    assert!(app.world().get::<Dragging>(card).is_none());
+}
+
+#[test]
+fn drag_losing_focus_is_dropped_and_returned() {
+    let mut app = headless_app();
+    let card = app
+        .world_mut()
+        .spawn((
+            square(Vec2::ZERO),
+            Draggable {
+                on_lost: LostCursor::Drop,
+                drop_outside: DropOutside::Return,
+                ..default()
+            },
+        ))
+        .id();
+    let window = app
+        .world_mut()
+        .query_filtered::<Entity, With<PrimaryWindow>>()
+        .single(app.world())
+        .unwrap();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    move_cursor(&mut app, Vec2::new(600.0, 300.0));
+    // The buttons are never released, as the window doesn't hear about it once it has lost focus
+    app.world_mut().send_event(WindowFocused {
+        window,
+        focused: false,
+    });
+    app.update();
+    assert_eq!(events::<Dropped>(&mut app).len(), 1);
+    assert!(app.world().get::<Dragging>(card).is_none());
+    let translation = app.world().get::<Transform>(card).unwrap().translation;
+    assert_eq!(translation, Vec3::ZERO);
+}
+
+#[test]
+fn entity_not_following_the_cursor_only_reports_its_movement() {
+    let mut app = headless_app();
+    let table = app.world_mut().spawn(Transform::default()).id();
+    let card = app
+        .world_mut()
+        .spawn((
+            square(Vec2::ZERO),
+            Draggable {
+                follow_cursor: false,
+                z_boost: Some(5.0),
+                ..default()
+            },
+            ChildOf(table),
+        ))
+        .id();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    events::<DragMove>(&mut app);
+    move_cursor(&mut app, Vec2::new(600.0, 300.0));
+    let moves = events::<DragMove>(&mut app);
+    assert_eq!(moves.len(), 1);
+    assert_eq!(moves[0].logical_delta, Vec2::new(200.0, 0.0));
+    assert_eq!(moves[0].world_delta, Vec2::new(200.0, 0.0));
+    assert_eq!(moves[0].cursor, Vec2::new(600.0, 300.0));
+    let translation = app.world().get::<Transform>(card).unwrap().translation;
+    assert_eq!(translation, Vec3::ZERO);
+    assert_eq!(app.world().get::<ChildOf>(card), Some(&ChildOf(table)));
+}
+
+#[test]
+fn grabbing_inserts_the_offset_from_the_entity_to_the_cursor() {
+    let mut app = headless_app();
+    let draggable = || Draggable {
+        insert_offset: true,
+        ..default()
+    };
+    // 100 by 100 logical pixel UI node whose top left corner is at (150, 150), laid out by hand
+    let node = app
+        .world_mut()
+        .spawn((
+            ComputedNode {
+                size: Vec2::splat(100.0),
+                inverse_scale_factor: 1.0,
+                ..default()
+            },
+            GlobalTransform::from_xyz(200.0, 200.0, 0.0),
+            draggable(),
+        ))
+        .id();
+    // Seen at the center of the window
+    let card = app
+        .world_mut()
+        .spawn((square(Vec2::ZERO), draggable()))
+        .id();
+
+    let offset = |app: &App, entity| {
+        let offset = app.world().get::<DragOffset>(entity).unwrap();
+        Vec2::new(offset.x, offset.y)
+    };
+    move_cursor(&mut app, Vec2::new(170.0, 180.0));
+    press(&mut app, MouseButton::Left);
+    assert_eq!(offset(&app, node), Vec2::new(20.0, 30.0));
+    release(&mut app, MouseButton::Left);
+
+    // Both are from the entity to the cursor in screen space, with y pointing down
+    move_cursor(&mut app, Vec2::new(420.0, 290.0));
+    press(&mut app, MouseButton::Left);
+    assert_eq!(offset(&app, card), Vec2::new(20.0, -10.0));
+}
+
+#[test]
+fn dwelling_is_triggered_on_the_dragged_entity_and_the_receiver() {
+    #[derive(Component)]
+    struct Dwelled;
+    let on_dwell = |trigger: Trigger<HoverDwell>, mut commands: Commands| {
+        commands.entity(trigger.target()).insert(Dwelled);
+    };
+
+    let mut app = headless_app();
+    let card = app
+        .world_mut()
+        .spawn((square(Vec2::ZERO), Draggable::default()))
+        .observe(on_dwell)
+        .id();
+    let folder = app
+        .world_mut()
+        .spawn((
+            square(Vec2::new(200.0, 0.0)),
+            Receiver,
+            DwellTrigger {
+                duration: 0.0,
+                repeat: None,
+            },
+        ))
+        .observe(on_dwell)
+        .id();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    move_cursor(&mut app, Vec2::new(600.0, 300.0));
+    assert!(app.world().get::<Dwelled>(card).is_some());
+    assert!(app.world().get::<Dwelled>(folder).is_some());
+}
+
+#[test]
+fn every_awaiting_entity_starts_when_its_own_wait_ends() {
+    let mut app = headless_app();
+    app.world_mut().resource_mut::<DragSettings>().hold_clock = HoldClock::Virtual;
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    let camera = app
+        .world_mut()
+        .query_filtered::<Entity, With<Camera>>()
+        .single(app.world())
+        .unwrap();
+    let now = app.world().resource::<Time<Virtual>>().elapsed_secs_f64();
+    // The entity waiting longer comes first, so it can't hold up the other one
+    let [second, first] = [2.0, 1.0].map(|wait| {
+        app.world_mut()
+            .spawn((
+                square(Vec2::ZERO),
+                Draggable::default(),
+                AwaitingDrag {
+                    started: now,
+                    ends: now + wait,
+                    progress: 0.0,
+                    grab_offset: Vec3::ZERO,
+                    camera,
+                    group: Vec::new(),
+                    screen_position: Vec2::new(400.0, 300.0),
+                    world_position: Vec2::ZERO,
+                },
+            ))
+            .id()
+    });
+
+    // The entities wait for as long as the button stays down over them
+    app.world_mut()
+        .resource_mut::<ButtonInput<MouseButton>>()
+        .press(MouseButton::Left);
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    let advance = |app: &mut App| {
+        app.world_mut()
+            .resource_mut::<Time<Virtual>>()
+            .advance_by(Duration::from_secs_f64(1.5));
+        app.update();
+    };
+    advance(&mut app);
+    assert!(app.world().get::<Dragging>(first).is_some());
+    assert!(app.world().get::<AwaitingDrag>(second).is_some());
+    advance(&mut app);
+    assert!(app.world().get::<Dragging>(second).is_some());
+}
+
+#[test]
+fn hold_tolerates_movement_within_its_slop() {
+    let mut app = headless_app();
+    app.world_mut().resource_mut::<DragSettings>().hold_clock = HoldClock::Virtual;
+    app.world_mut().resource_mut::<Time<Virtual>>().pause();
+    let card = app
+        .world_mut()
+        .spawn((square(Vec2::ZERO), Draggable::with_hold(1.0).hold_slop(8.0)))
+        .id();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    move_cursor(&mut app, Vec2::new(405.0, 300.0));
+    app.world_mut()
+        .resource_mut::<Time<Virtual>>()
+        .advance_by(Duration::from_secs_f64(1.5));
+    app.update();
+    assert!(app.world().get::<Dragging>(card).is_some());
+    let dragged = events::<Dragged>(&mut app);
+    assert_eq!(dragged[0].screen_position, Some(Vec2::new(405.0, 300.0)));
+
+    release(&mut app, MouseButton::Left);
+    press(&mut app, MouseButton::Left);
+    assert!(app.world().get::<AwaitingDrag>(card).is_some());
+    move_cursor(&mut app, Vec2::new(420.0, 300.0));
+    assert!(app.world().get::<AwaitingDrag>(card).is_none());
+    assert_eq!(events::<DragAwaitCancelled>(&mut app).len(), 1);
+
+    // The button is still held over the entity, but a cancelled hold only starts again with a new click
+    app.update();
+    assert!(app.world().get::<AwaitingDrag>(card).is_none());
+    assert!(app.world().get::<Dragging>(card).is_none());
+}
+
+#[test]
+fn sprite_with_unloaded_image_is_skipped_until_it_has_a_size() {
+    let mut app = headless_app();
+    let image = app
+        .world_mut()
+        .resource_mut::<Assets<Image>>()
+        .reserve_handle();
+    let card = app
+        .world_mut()
+        .spawn((Sprite::from_image(image), Draggable::default()))
+        .id();
+
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    press(&mut app, MouseButton::Left);
+    assert!(events::<Dragged>(&mut app).is_empty());
+    release(&mut app, MouseButton::Left);
+
+    // Sprites given a size don't need their image to be hit
+    app.world_mut().get_mut::<Sprite>(card).unwrap().custom_size = Some(Vec2::splat(100.0));
+    press(&mut app, MouseButton::Left);
+    assert_eq!(events::<Dragged>(&mut app).len(), 1);
+}
+
+#[test]
+fn nested_draggable_is_grabbed_before_the_one_drawn_on_top() {
+    let mut world = World::new();
+    let [board, piece, card, other_card] = [(); 4].map(|_| world.spawn_empty().id());
+    let order = |distance| DrawOrder { ui: None, distance };
+    let ancestors_of = |entity| if entity == piece { vec![board] } else { vec![] };
+
+    let candidates = vec![(board, order(1.0), ()), (piece, order(5.0), ())];
+    assert_eq!(
+        select_candidate(candidates, ancestors_of),
+        Some((piece, ()))
+    );
+    let candidates = vec![(card, order(1.0), ()), (other_card, order(1.0), ())];
+    assert_eq!(
+        select_candidate(candidates, ancestors_of),
+        Some((other_card, ()))
+    );
+}
+
+#[test]
+fn builder_keeps_required_and_disallowed_inputs_apart() {
+    let draggable = Draggable::new()
+        .require(InputFlags::LeftClick | InputFlags::Ctrl)
+        .disallow(InputFlags::RightClick | InputFlags::Ctrl);
+    assert_eq!(draggable.required, InputFlags::LeftClick);
+    assert_eq!(
+        draggable.disallowed,
+        InputFlags::RightClick | InputFlags::Ctrl
+    );
+
+    let draggable = Draggable::new().require(InputFlags::RightClick);
+    assert_eq!(draggable.disallowed, InputFlags::MiddleClick);
+}
+
+#[test]
+fn exact_match_allows_extra_modifiers_but_not_clicks() {
+    let required = InputFlags::LeftClick | InputFlags::Ctrl;
+    assert!((required | InputFlags::Shift).matches_exactly(required));
+    assert!(!(required | InputFlags::RightClick).matches_exactly(required));
+    assert!(!InputFlags::LeftClick.matches_exactly(required));
+}
+
+#[test]
+fn input_map_rebinds_keys_to_modifiers() {
+    let mut input_map = DragInputMap::default();
+    input_map.keys.insert(KeyCode::SuperLeft, InputFlags::Ctrl);
+    let mut keys = ButtonInput::<KeyCode>::default();
+    keys.press(KeyCode::SuperLeft);
+    let mut buttons = ButtonInput::<MouseButton>::default();
+    buttons.press(MouseButton::Left);
+    assert_eq!(
+        input_map.flags(&keys, &buttons),
+        InputFlags::LeftClick | InputFlags::Ctrl
+    );
+}
+
+#[test]
+fn input_map_binds_other_buttons_to_extra_clicks() {
+    let mut input_map = DragInputMap::default();
+    input_map
+        .buttons
+        .insert(MouseButton::Other(8), InputFlags::Extra1);
+    let keys = ButtonInput::<KeyCode>::default();
+    let mut buttons = ButtonInput::<MouseButton>::default();
+    buttons.press(MouseButton::Other(8));
+    buttons.press(MouseButton::Other(9));
+    let inputs = input_map.flags(&keys, &buttons);
+    assert_eq!(inputs, InputFlags::Extra1);
+    assert!(inputs.any_click());
+}
+
+#[test]
+fn hold_progress_goes_from_zero_to_one() {
+    let awaiting = AwaitingDrag {
+        started: 2.0,
+        ends: 2.5,
+        progress: 0.0,
+        grab_offset: Vec3::ZERO,
+        camera: Entity::PLACEHOLDER,
+        group: Vec::new(),
+        screen_position: Vec2::ZERO,
+        world_position: Vec2::ZERO,
+    };
+    assert_eq!(awaiting.progress_at(2.0), 0.0);
+    assert_eq!(awaiting.progress_at(2.25), 0.5);
+    assert_eq!(awaiting.progress_at(3.0), 1.0);
+}
+
+#[test]
+fn receivers_only_accept_matching_groups() {
+    let mut world = World::new();
+    let card = world.spawn(DragGroup(0b01)).id();
+    let token = world.spawn(DragGroup(0b10)).id();
+    let ungrouped = world.spawn_empty().id();
+    let hand_slot = world.spawn(DragAccepts(0b01)).id();
+    let any_slot = world.spawn_empty().id();
+
+    let mut state = SystemState::<
+        Query<(Option<&DragKind>, Option<&DragGroup>, Option<&DragAccepts>)>,
+    >::new(&mut world);
+    let q_kinds = state.get(&world);
+    assert!(kinds_match(&q_kinds, card, hand_slot));
+    assert!(!kinds_match(&q_kinds, token, hand_slot));
+    assert!(!kinds_match(&q_kinds, ungrouped, hand_slot));
+    assert!(kinds_match(&q_kinds, token, any_slot));
+    assert!(kinds_match(&q_kinds, ungrouped, any_slot));
+}
+
+#[test]
+fn receivers_are_ordered_by_priority_then_depth() {
+    let mut world = hit_test_world();
+    let sprite = Sprite::from_color(Color::WHITE, Vec2::splat(100.0));
+    let board = world
+        .spawn((sprite.clone(), GlobalTransform::IDENTITY))
+        .id();
+    let slot = world
+        .spawn((sprite.clone(), GlobalTransform::IDENTITY))
+        .id();
+    let tile = world
+        .spawn((sprite.clone(), GlobalTransform::IDENTITY))
+        .id();
+    // Tied with the board, and spawned after it
+    let cover = world.spawn((sprite, GlobalTransform::IDENTITY)).id();
+    world.entity_mut(slot).insert(ReceiverPriority(1));
+
+    let mut state =
+        SystemState::<(Query<(HitTarget, Option<&ReceiverPriority>)>, SpriteSizes)>::new(
+            &mut world,
+        );
+    let (q_receivers, sprite_sizes) = state.get(&world);
+    let mut index = ReceiverIndex::default();
+    // The tile is nested one level deep, in the board
+    let targets = q_receivers.iter().map(|(target, priority)| {
+        let depth = usize::from(target.entity == tile);
+        (target, priority, depth)
+    });
+    index.rebuild(targets, &sprite_sizes);
+    let hits = index.receivers_at(&cursor_at(Vec2::ZERO, 1.0), &sprite_sizes.assets);
+    assert_eq!(hits, vec![slot, tile, cover, board]);
+}
+
+#[test]
+fn nearest_volume_along_the_ray_is_on_top() {
+    let mut world = hit_test_world();
+    let aabb = Aabb::from_min_max(Vec3::splat(-1.0), Vec3::splat(1.0));
+    let near = world
+        .spawn((aabb, GlobalTransform::from_xyz(0.0, 0.0, 0.0)))
+        .id();
+    // Higher up, but further along a ray looking sideways
+    let far = world
+        .spawn((aabb, GlobalTransform::from_xyz(5.0, 0.0, 0.5)))
+        .id();
+    let cursor = CursorPosition {
+        ray: Ray3d::new(Vec3::new(-10.0, 0.0, 0.0), Dir3::X),
+        ..cursor_at(Vec2::ZERO, 1.0)
+    };
+
+    let mut state =
+        SystemState::<(Query<(HitTarget, Option<&ReceiverPriority>)>, SpriteSizes)>::new(
+            &mut world,
+        );
+    let (q_targets, sprite_sizes) = state.get(&world);
+    let candidates = q_targets
+        .iter()
+        .map(|(target, _)| {
+            let order = DrawOrder::new(&target, &sprite_sizes, &cursor);
+            (target.entity, order, ())
+        })
+        .collect();
+    assert_eq!(select_candidate(candidates, |_| vec![]), Some((near, ())));
+
+    let mut index = ReceiverIndex::default();
+    index.rebuild(
+        q_targets
+            .iter()
+            .map(|(target, priority)| (target, priority, 0)),
+        &sprite_sizes,
+    );
+    assert_eq!(
+        index.receivers_at(&cursor, &sprite_sizes.assets),
+        vec![near, far]
+    );
+}
+
+#[test]
+fn insertion_index_counts_the_other_items_before_the_cursor() {
+    let mut world = hit_test_world();
+    world.init_resource::<ReceiverIndex>();
+    let items: Vec<Entity> = [50.0, 150.0, 250.0]
+        .map(|y| {
+            world
+                .spawn((
+                    ComputedNode::default(),
+                    GlobalTransform::from_xyz(50.0, y, 0.0),
+                ))
+                .id()
+        })
+        .into();
+    let list = world
+        .spawn(ReorderList::default())
+        .add_children(&items)
+        .id();
+    let dragged = world.spawn_empty().id();
+
+    let mut state = SystemState::<ReceiverLookup>::new(&mut world);
+    let receivers = state.get(&world);
+    let cursor = cursor_at(Vec2::new(50.0, 200.0), 1.0);
+    assert_eq!(
+        receivers.insertion_index(list, &cursor, dragged),
+        Some((list, 2))
+    );
+    // The dragged item doesn't count, and hovering over an item counts as hovering over its list
+    assert_eq!(
+        receivers.insertion_index(items[2], &cursor, items[0]),
+        Some((list, 1))
+    );
+}
+
+#[test]
+fn ui_node_is_hit_at_every_scale_factor() {
+    for scale_factor in SCALE_FACTORS {
+        let (mut world, node) = ui_world(scale_factor);
+        for inside in [
+            Vec2::new(151.0, 151.0),
+            Vec2::new(200.0, 200.0),
+            Vec2::new(249.0, 249.0),
+        ] {
+            assert!(
+                is_hit(&mut world, node, cursor_at(inside, scale_factor)),
+                "{inside} at {scale_factor}"
+            );
+        }
+        for outside in [
+            Vec2::new(149.0, 200.0),
+            Vec2::new(251.0, 200.0),
+            Vec2::new(200.0, 251.0),
+        ] {
+            assert!(
+                !is_hit(&mut world, node, cursor_at(outside, scale_factor)),
+                "{outside} at {scale_factor}"
+            );
+        }
+    }
+}
+
+#[test]
+fn ui_hitbox_is_scaled_with_the_node() {
+    for scale_factor in SCALE_FACTORS {
+        let (mut world, node) = ui_world(scale_factor);
+        world
+            .entity_mut(node)
+            .insert(DragHitbox::rect(Vec2::splat(20.0), Vec2::new(30.0, 0.0)));
+        assert!(is_hit(
+            &mut world,
+            node,
+            cursor_at(Vec2::new(239.0, 209.0), scale_factor)
+        ));
+        assert!(!is_hit(
+            &mut world,
+            node,
+            cursor_at(Vec2::new(200.0, 200.0), scale_factor)
+        ));
+
+        world
+            .entity_mut(node)
+            .insert(DragHitbox::circle(10.0, Vec2::ZERO));
+        assert!(is_hit(
+            &mut world,
+            node,
+            cursor_at(Vec2::new(209.0, 200.0), scale_factor)
+        ));
+        assert!(!is_hit(
+            &mut world,
+            node,
+            cursor_at(Vec2::new(211.0, 200.0), scale_factor)
+        ));
+    }
+}
+
+#[test]
+fn absolute_ui_hitbox_ignores_the_node_position() {
+    for scale_factor in SCALE_FACTORS {
+        let (mut world, node) = ui_world(scale_factor);
+        world
+            .entity_mut(node)
+            .insert(DragHitbox::absolute(Rect::new(0.0, 0.0, 50.0, 50.0)));
+        assert!(is_hit(
+            &mut world,
+            node,
+            cursor_at(Vec2::new(49.0, 49.0), scale_factor)
+        ));
+        assert!(!is_hit(
+            &mut world,
+            node,
+            cursor_at(Vec2::new(200.0, 200.0), scale_factor)
+        ));
+    }
+}
+
+#[test]
+fn ui_clip_is_compared_in_physical_pixels() {
+    for scale_factor in SCALE_FACTORS {
+        let (mut world, node) = ui_world(scale_factor);
+        // Only the left half of the node is visible
+        world.entity_mut(node).insert(CalculatedClip {
+            clip: Rect::new(0.0, 0.0, 200.0 * scale_factor, 400.0 * scale_factor),
+        });
+        assert!(is_hit(
+            &mut world,
+            node,
+            cursor_at(Vec2::new(190.0, 200.0), scale_factor)
+        ));
+        assert!(!is_hit(
+            &mut world,
+            node,
+            cursor_at(Vec2::new(210.0, 200.0), scale_factor)
+        ));
+    }
+}
+
+#[test]
+fn ui_grab_offset_is_in_val_px_units() {
+    for scale_factor in SCALE_FACTORS {
+        let (mut world, node) = ui_world(scale_factor);
+        let cursor = cursor_at(Vec2::new(170.0, 180.0), scale_factor);
+        let offset = grab_offset(&mut world, node, GrabMode::Cursor, cursor);
+        assert!(
+            offset.abs_diff_eq(Vec3::new(20.0, 30.0, 0.0), 1e-3),
+            "{offset} at {scale_factor}"
+        );
+        let offset = grab_offset(&mut world, node, GrabMode::Center, cursor);
+        assert!(
+            offset.abs_diff_eq(Vec3::new(50.0, 50.0, 0.0), 1e-3),
+            "{offset} at {scale_factor}"
+        );
+    }
+}
+
+#[test]
+fn dragged_ui_node_follows_the_cursor_in_val_px_units() {
+    for scale_factor in SCALE_FACTORS {
+        let mut app = headless_app();
+        let window = app
+            .world_mut()
+            .query_filtered::<Entity, With<PrimaryWindow>>()
+            .single(app.world())
+            .unwrap();
+        let mut q_windows = app.world_mut().query::<&mut Window>();
+        q_windows
+            .single_mut(app.world_mut())
+            .unwrap()
+            .resolution
+            .set_scale_factor_override(Some(scale_factor));
+        // Sent by winit along with the change, and needed for the camera to pick it up
+        app.world_mut().send_event(WindowScaleFactorChanged {
+            window,
+            scale_factor: scale_factor.into(),
+        });
+        // Laid out by hand like `ui_world`, as there is no UI plugin to do it
+        let node = app
+            .world_mut()
+            .spawn((
+                Node::default(),
+                ComputedNode {
+                    size: Vec2::splat(100.0 * scale_factor),
+                    inverse_scale_factor: scale_factor.recip(),
+                    ..default()
+                },
+                Transform::from_translation(Vec3::new(200.0, 200.0, 0.0) * scale_factor),
+                Draggable::default(),
+            ))
+            .id();
+
+        // Started by command, as nodes can't be told which camera they render to without the UI plugin
+        move_cursor(&mut app, Vec2::new(170.0, 180.0));
+        app.world_mut().send_event(DragCommand::Start(node));
+        app.update();
+        assert!(
+            app.world().get::<Dragging>(node).is_some(),
+            "at {scale_factor}"
+        );
+
+        // Held 20 by 30 logical pixels from its top left corner
+        move_cursor(&mut app, Vec2::new(300.0, 250.0));
+        let style = app.world().get::<Node>(node).unwrap();
+        assert_eq!(
+            (style.left, style.top),
+            (Val::Px(280.0), Val::Px(220.0)),
+            "at {scale_factor}"
+        );
+    }
+}
+
+#[test]
+fn ui_node_stacked_on_a_receiver_is_laid_out_where_it_was() {
+    let mut app = headless_app();
+    let node = app
+        .world_mut()
+        .spawn((
+            Node::default(),
+            ComputedNode {
+                size: Vec2::splat(100.0),
+                ..default()
+            },
+            Transform::from_xyz(200.0, 200.0, 0.0),
+            Draggable::default(),
+        ))
+        .id();
+    app.world_mut().spawn(Node::default()).add_child(node);
+    let slot = app
+        .world_mut()
+        .spawn((square(Vec2::new(200.0, 0.0)), Receiver, ReceiverMode::Stack))
+        .id();
+
+    // Started by command, as nodes can't be told which camera they render to without the UI plugin
+    move_cursor(&mut app, Vec2::new(400.0, 300.0));
+    app.world_mut().send_event(DragCommand::Start(node));
+    app.update();
+    move_cursor(&mut app, Vec2::new(600.0, 300.0));
+    let style = app.world().get::<Node>(node).unwrap();
+    assert_eq!(style.position_type, PositionType::Absolute);
+
+    app.world_mut().send_event(DragCommand::Drop(node));
+    app.update();
+    assert_eq!(events::<Dropped>(&mut app)[0].received, Some(slot));
+    assert_eq!(app.world().get::<ChildOf>(node).unwrap().parent(), slot);
+    let style = app.world().get::<Node>(node).unwrap();
+    assert_eq!(
+        (style.position_type, style.left, style.top),
+        (PositionType::Relative, Val::Auto, Val::Auto)
+    );
+}
+
+#[test]
+fn sprite_with_unloaded_image_has_no_bounds_until_it_has_a_size() {
+    let mut world = World::new();
+    world.init_resource::<SpriteSizeCache>();
+    world.init_resource::<Assets<Image>>();
+    world.init_resource::<Assets<TextureAtlasLayout>>();
+    let image = world.resource::<Assets<Image>>().reserve_handle();
+    let card = world
+        .spawn((Sprite::from_image(image), GlobalTransform::IDENTITY))
+        .id();
+    let bounds = |world: &mut World| {
+        let mut state = SystemState::<(Query<HitTarget>, SpriteSizes)>::new(world);
+        let (q_targets, sprite_sizes) = state.get(world);
+        get_bounds(&q_targets.get(card).unwrap(), &sprite_sizes)
+    };
+
+    // Clicking it while the image loads must neither panic nor hit it
+    assert!(bounds(&mut world).is_none());
+
+    // Sprites given a size don't need their image to be hit
+    world.get_mut::<Sprite>(card).unwrap().custom_size = Some(Vec2::splat(100.0));
+    let Some(Bounds::World(bounding_box)) = bounds(&mut world) else {
+        panic!("sprite with a custom size should have world bounds");
+    };
+    assert_eq!(bounding_box.size(), Vec2::splat(100.0));
+}